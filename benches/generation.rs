@@ -0,0 +1,78 @@
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use passgen::{
+    PasswordOptions, generate_multiple_passwords, generate_password_with_rng,
+    generate_pattern_password_with_rng, generate_phonetic_password_with_rng,
+};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::time::Duration;
+
+fn all_sets(length: usize) -> PasswordOptions {
+    PasswordOptions {
+        length,
+        uppercase: true,
+        lowercase: true,
+        numbers: true,
+        special: true,
+        ..Default::default()
+    }
+}
+
+fn generators(c: &mut Criterion) {
+    let opts = all_sets(16);
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut group = c.benchmark_group("generators");
+    group.throughput(Throughput::Elements(1));
+
+    group.bench_function("default", |b| {
+        b.iter(|| generate_password_with_rng(black_box(&opts), &mut rng))
+    });
+    group.bench_function("phonetic", |b| {
+        b.iter(|| generate_phonetic_password_with_rng(black_box(&opts), &mut rng))
+    });
+    group.bench_function("pattern", |b| {
+        b.iter(|| {
+            generate_pattern_password_with_rng(black_box("ULLLLDDSULLLLDDS"), &opts, &mut rng)
+        })
+    });
+    group.finish();
+}
+
+/// Bulk provisioning: raw generation without strength analysis
+fn bulk_generation(c: &mut Criterion) {
+    let opts = all_sets(16);
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut group = c.benchmark_group("bulk_generation");
+    group.sample_size(10);
+
+    for count in [1_000usize, 1_000_000] {
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, &count| {
+            b.iter(|| {
+                (0..count)
+                    .map(|_| generate_password_with_rng(&opts, &mut rng))
+                    .collect::<Vec<_>>()
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Generation plus strength analysis of every password
+fn multiple_passwords(c: &mut Criterion) {
+    let opts = all_sets(16);
+    let mut group = c.benchmark_group("generate_multiple_passwords");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(30));
+
+    for count in [1_000usize, 1_000_000] {
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, &count| {
+            b.iter(|| generate_multiple_passwords(black_box(&opts), count))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, generators, bulk_generation, multiple_passwords);
+criterion_main!(benches);
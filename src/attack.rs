@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// An attacker scenario: how fast guesses against the password can be made.
+///
+/// Rates follow zxcvbn's reference scenarios and can be overridden per
+/// deployment, e.g. `bcrypt=2e4` for a measured bcrypt cost factor.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AttackerModel {
+    pub name: String,
+    pub guesses_per_second: f64,
+}
+
+/// Built-in scenarios; the first one drives `crack_time_seconds`
+const BUILTIN: [(&str, f64); 5] = [
+    // Unsalted MD5/SHA-1/NTLM on a single machine
+    ("offline_fast_hash", 1e10),
+    // 100 attempts per hour before lockout or rate limiting kicks in
+    ("online_throttled", 100.0 / 3600.0),
+    ("online_unthrottled", 10.0),
+    // bcrypt, scrypt, PBKDF2 or Argon2 with a sensible work factor
+    ("offline_slow_hash", 1e4),
+    // A rack of GPUs against a fast hash
+    ("gpu_cluster", 1e12),
+];
+
+impl AttackerModel {
+    pub fn new(name: impl Into<String>, guesses_per_second: f64) -> Self {
+        AttackerModel {
+            name: name.into(),
+            guesses_per_second,
+        }
+    }
+
+    /// The built-in scenarios, starting with the offline fast hash attacker
+    pub fn defaults() -> Vec<AttackerModel> {
+        BUILTIN
+            .iter()
+            .map(|&(name, rate)| AttackerModel::new(name, rate))
+            .collect()
+    }
+
+    /// Look up a built-in scenario by name
+    pub fn builtin(name: &str) -> Option<AttackerModel> {
+        BUILTIN
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|&(name, rate)| AttackerModel::new(name, rate))
+    }
+
+    /// Seconds needed to make `10^guesses_log10` guesses, as a power of ten
+    pub fn seconds_log10_for(&self, guesses_log10: f64) -> f64 {
+        guesses_log10 - self.guesses_per_second.log10()
+    }
+
+    /// Seconds needed to make `10^guesses_log10` guesses, saturating at
+    /// `f64::MAX` instead of overflowing to infinity
+    pub fn seconds_for(&self, guesses_log10: f64) -> f64 {
+        10f64
+            .powf(self.seconds_log10_for(guesses_log10))
+            .min(f64::MAX)
+    }
+}
+
+/// Parses `NAME` (a built-in scenario) or `NAME=RATE` with RATE in guesses per second
+impl FromStr for AttackerModel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((name, rate)) = s.split_once('=') else {
+            return AttackerModel::builtin(s).ok_or_else(|| {
+                let names: Vec<&str> = BUILTIN.iter().map(|(name, _)| *name).collect();
+                format!(
+                    "unknown attacker '{}' (expected NAME=GUESSES_PER_SECOND or one of {})",
+                    s,
+                    names.join(", ")
+                )
+            });
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(format!("attacker '{}' has no name", s));
+        }
+        match rate.trim().parse::<f64>() {
+            Ok(rate) if rate.is_finite() && rate > 0.0 => Ok(AttackerModel::new(name, rate)),
+            _ => Err(format!(
+                "invalid guess rate '{}' for attacker '{}' (expected a positive number such as 1e4)",
+                rate, name
+            )),
+        }
+    }
+}
+
+impl fmt::Display for AttackerModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({:.0e} guesses/s)",
+            self.name, self.guesses_per_second
+        )
+    }
+}
+
+/// Crack time of a password under one [`AttackerModel`]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CrackTime {
+    pub attacker: String,
+    pub guesses_per_second: f64,
+    pub seconds: f64,
+    /// `seconds` as a power of ten, exact even when `seconds` saturates
+    #[serde(default)]
+    pub seconds_log10: f64,
+    pub display: String,
+}
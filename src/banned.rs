@@ -0,0 +1,91 @@
+use std::path::Path;
+use std::{fs, io};
+
+/// Organization-specific terms (product names, cities, sports teams) that
+/// must not appear in passwords.
+///
+/// Matching ignores case and common character substitutions, so a banned
+/// "acme" also catches "ACME", "4cm3" and "@cme".
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BannedWords {
+    words: Vec<String>,
+}
+
+impl BannedWords {
+    pub fn new<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut banned = BannedWords::default();
+        for word in words {
+            let word = word.as_ref().trim().to_lowercase();
+            if !word.is_empty() && !banned.words.contains(&word) {
+                banned.words.push(word);
+            }
+        }
+        banned
+    }
+
+    /// Parse one term per line; blank lines and lines starting with `#` are
+    /// skipped.
+    pub fn parse(text: &str) -> Self {
+        Self::new(
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.starts_with('#')),
+        )
+    }
+
+    /// Load a banned-word file (see [`BannedWords::parse`] for the format).
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::parse(&fs::read_to_string(path)?))
+    }
+
+    /// The terms, lowercased
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// The first banned term found in `password`, if any.
+    ///
+    /// Walks the password in place rather than collecting it, so no unwiped
+    /// copy is left behind.
+    pub fn find(&self, password: &str) -> Option<&str> {
+        self.words
+            .iter()
+            .find(|word| {
+                password.char_indices().any(|(start, _)| {
+                    let mut rest = password[start..].chars();
+                    word.chars()
+                        .all(|w| rest.next().is_some_and(|c| resembles(c, w)))
+                })
+            })
+            .map(String::as_str)
+    }
+}
+
+/// Whether password character `c` could stand for lowercase word character `w`
+fn resembles(c: char, w: char) -> bool {
+    if c.to_lowercase().eq(w.to_lowercase()) {
+        return true;
+    }
+    let stands_for: &[char] = match c {
+        '0' => &['o'],
+        '1' => &['i', 'l'],
+        '!' => &['i'],
+        '|' => &['i', 'l'],
+        '3' => &['e'],
+        '4' | '@' => &['a'],
+        '5' | '$' => &['s'],
+        '7' | '+' => &['t'],
+        '8' => &['b'],
+        '9' => &['g'],
+        _ => &[],
+    };
+    stands_for.contains(&w)
+}
@@ -0,0 +1,229 @@
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::BreachCheck;
+use crate::PassgenError;
+
+/// First bytes of a binary index written by [`build_index`]
+const INDEX_MAGIC: &[u8; 8] = b"PGSHA1\x00\x01";
+/// A SHA-1 digest followed by a big-endian `u32` count
+const RECORD_LEN: u64 = 24;
+
+/// A local copy of the Pwned Passwords dataset.
+///
+/// Two layouts are understood and told apart by their first bytes:
+///
+/// - the text dump ordered by hash, one `SHA1:COUNT` line per password, as
+///   produced by the official downloader (`HASH` alone counts as 1);
+/// - a binary index built from it with [`build_index`], about half the size and
+///   searched with fixed-size reads.
+///
+/// Neither is loaded into memory: each lookup is a binary search over the file,
+/// so multi-gigabyte dumps are fine.
+pub struct BreachCorpus {
+    path: PathBuf,
+    layout: Layout,
+    reader: Mutex<BufReader<File>>,
+}
+
+#[derive(Clone, Copy)]
+enum Layout {
+    Text { len: u64 },
+    Index { records: u64 },
+}
+
+impl BreachCorpus {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, PassgenError> {
+        let path = path.as_ref().to_path_buf();
+        let err = |e: io::Error| breach_error(&path, e);
+        let mut file = File::open(&path).map_err(err)?;
+        let len = file.metadata().map_err(err)?.len();
+
+        let mut magic = [0; 8];
+        let is_index = len >= 8 && {
+            file.read_exact(&mut magic).map_err(err)?;
+            &magic == INDEX_MAGIC
+        };
+        let layout = if is_index {
+            if (len - 8) % RECORD_LEN != 0 {
+                return Err(PassgenError::Breach(format!(
+                    "{}: truncated index",
+                    path.display()
+                )));
+            }
+            Layout::Index {
+                records: (len - 8) / RECORD_LEN,
+            }
+        } else {
+            Layout::Text { len }
+        };
+
+        // Reject files that are not a hash dump up front rather than on the
+        // first lookup that happens to land on a bad line
+        let mut reader = BufReader::new(file);
+        if let Layout::Text { len } = layout
+            && len > 0
+            && line_at(&mut reader, 0).map_err(err)?.is_none()
+        {
+            return Err(PassgenError::Breach(format!(
+                "{}: not a SHA-1 hash list or passgen index",
+                path.display()
+            )));
+        }
+
+        Ok(BreachCorpus {
+            path,
+            layout,
+            reader: Mutex::new(reader),
+        })
+    }
+}
+
+impl BreachCheck for BreachCorpus {
+    fn count_sha1(&self, digest: &[u8; 20]) -> Result<u64, PassgenError> {
+        let mut reader = self.reader.lock().unwrap();
+        let found = match self.layout {
+            Layout::Text { len } => search_text(&mut *reader, len, digest),
+            Layout::Index { records } => search_index(&mut *reader, records, digest),
+        };
+        found.map_err(|e| breach_error(&self.path, e))
+    }
+}
+
+/// Convert a sorted `SHA1:COUNT` dump into the binary index format read by
+/// [`BreachCorpus`], returning the number of hashes written
+pub fn build_index(text: impl BufRead, mut out: impl Write) -> Result<u64, PassgenError> {
+    let err = |e: io::Error| PassgenError::Breach(e.to_string());
+    out.write_all(INDEX_MAGIC).map_err(err)?;
+
+    let mut previous: Option<[u8; 20]> = None;
+    let mut records = 0;
+    for (number, line) in text.lines().enumerate() {
+        let line = line.map_err(err)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some((digest, count)) = parse_line(&line) else {
+            return Err(PassgenError::Breach(format!(
+                "line {} is not a SHA1:COUNT entry",
+                number + 1
+            )));
+        };
+        if previous.is_some_and(|previous| previous >= digest) {
+            return Err(PassgenError::Breach(format!(
+                "line {} is out of order; use the dump ordered by hash",
+                number + 1
+            )));
+        }
+        previous = Some(digest);
+
+        out.write_all(&digest).map_err(err)?;
+        out.write_all(&(count.min(u32::MAX as u64) as u32).to_be_bytes())
+            .map_err(err)?;
+        records += 1;
+    }
+    out.flush().map_err(err)?;
+    Ok(records)
+}
+
+fn breach_error(path: &Path, err: io::Error) -> PassgenError {
+    PassgenError::Breach(format!("{}: {}", path.display(), err))
+}
+
+/// Binary search over byte offsets of a sorted text dump.
+///
+/// `lo` is always the start of a line whose predecessors all sort below
+/// `digest`; lines starting at or after `hi` all sort above it.
+fn search_text<R: BufRead + Seek>(reader: &mut R, len: u64, digest: &[u8; 20]) -> io::Result<u64> {
+    let (mut lo, mut hi) = (0, len);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let start = next_line_start(reader, mid)?;
+        if start >= hi {
+            hi = mid;
+            continue;
+        }
+        let Some((entry, count, end)) = line_at(reader, start)? else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line at byte {} is not a SHA1:COUNT entry", start),
+            ));
+        };
+        match entry.cmp(digest) {
+            Ordering::Less => lo = end,
+            Ordering::Equal => return Ok(count),
+            Ordering::Greater => hi = mid,
+        }
+    }
+    Ok(0)
+}
+
+/// Offset of the first line starting at or after `pos`
+fn next_line_start<R: BufRead + Seek>(reader: &mut R, pos: u64) -> io::Result<u64> {
+    if pos == 0 {
+        return Ok(0);
+    }
+    reader.seek(SeekFrom::Start(pos - 1))?;
+    let skipped = reader.skip_until(b'\n')?;
+    Ok(pos - 1 + skipped as u64)
+}
+
+/// Parse the line starting at `start`, returning its digest, count and the
+/// offset just past it; `None` if the line is malformed
+fn line_at<R: BufRead + Seek>(
+    reader: &mut R,
+    start: u64,
+) -> io::Result<Option<([u8; 20], u64, u64)>> {
+    reader.seek(SeekFrom::Start(start))?;
+    let mut line = String::new();
+    let read = reader.read_line(&mut line)?;
+    Ok(parse_line(&line).map(|(digest, count)| (digest, count, start + read as u64)))
+}
+
+fn parse_line(line: &str) -> Option<([u8; 20], u64)> {
+    let line = line.trim_end();
+    let (hash, count) = match line.split_once(':') {
+        Some((hash, count)) => (hash, count.trim().parse().ok()?),
+        None => (line, 1),
+    };
+    Some((parse_hex(hash)?, count))
+}
+
+fn parse_hex(hash: &str) -> Option<[u8; 20]> {
+    let bytes = hash.as_bytes();
+    if bytes.len() != 40 {
+        return None;
+    }
+    let mut digest = [0; 20];
+    for (i, pair) in bytes.chunks(2).enumerate() {
+        let pair = std::str::from_utf8(pair).ok()?;
+        digest[i] = u8::from_str_radix(pair, 16).ok()?;
+    }
+    Some(digest)
+}
+
+fn search_index<R: Read + Seek>(
+    reader: &mut R,
+    records: u64,
+    digest: &[u8; 20],
+) -> io::Result<u64> {
+    let (mut lo, mut hi) = (0, records);
+    let mut record = [0; RECORD_LEN as usize];
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        reader.seek(SeekFrom::Start(INDEX_MAGIC.len() as u64 + mid * RECORD_LEN))?;
+        reader.read_exact(&mut record)?;
+        match record[..20].cmp(&digest[..]) {
+            Ordering::Less => lo = mid + 1,
+            Ordering::Equal => {
+                let count = u32::from_be_bytes(record[20..].try_into().unwrap());
+                return Ok(count as u64);
+            }
+            Ordering::Greater => hi = mid,
+        }
+    }
+    Ok(0)
+}
@@ -0,0 +1,33 @@
+use sha1_smol::Sha1;
+
+use crate::PassgenError;
+
+mod corpus;
+#[cfg(feature = "hibp")]
+mod range;
+
+pub use corpus::{BreachCorpus, build_index};
+#[cfg(feature = "hibp")]
+pub use range::RangeClient;
+
+/// A source of known-breached passwords, keyed by SHA-1 as in Pwned Passwords
+pub trait BreachCheck {
+    /// Number of times the password with SHA-1 `digest` was seen (0 when never)
+    fn count_sha1(&self, digest: &[u8; 20]) -> Result<u64, PassgenError>;
+
+    /// Number of times `password` was seen in breaches (0 when never)
+    fn count(&self, password: &str) -> Result<u64, PassgenError> {
+        self.count_sha1(&sha1(password))
+    }
+}
+
+/// SHA-1 digest of a password, as used by Pwned Passwords
+pub fn sha1(password: &str) -> [u8; 20] {
+    Sha1::from(password).digest().bytes()
+}
+
+/// Uppercase hexadecimal, the form Pwned Passwords uses for hashes
+#[cfg_attr(not(feature = "hibp"), allow(dead_code))]
+pub(crate) fn to_hex(digest: &[u8; 20]) -> String {
+    digest.iter().map(|byte| format!("{:02X}", byte)).collect()
+}
@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+use super::{BreachCheck, to_hex};
+use crate::PassgenError;
+
+/// Client for the Pwned Passwords range API.
+///
+/// Only the first five hex characters of a password's SHA-1 leave the
+/// machine; the server answers with every suffix sharing that prefix and the
+/// match happens locally (k-anonymity). With padding enabled the server also
+/// returns decoy suffixes with a count of 0, so response sizes do not reveal
+/// the prefix either.
+pub struct RangeClient {
+    base_url: String,
+    padding: bool,
+    agent: ureq::Agent,
+}
+
+impl RangeClient {
+    pub const DEFAULT_BASE_URL: &'static str = "https://api.pwnedpasswords.com/range/";
+
+    /// Client for the public API, with padding enabled
+    pub fn new() -> Self {
+        Self::with_base_url(Self::DEFAULT_BASE_URL)
+    }
+
+    /// Client for a mirror serving the same protocol, e.g.
+    /// `https://hibp.internal/range/` (the prefix is appended to the URL)
+    pub fn with_base_url(base_url: impl Into<String>) -> Self {
+        let mut base_url = base_url.into();
+        if !base_url.ends_with('/') {
+            base_url.push('/');
+        }
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(10))
+            .user_agent(concat!("passgen/", env!("CARGO_PKG_VERSION")))
+            .build();
+        RangeClient {
+            base_url,
+            padding: true,
+            agent,
+        }
+    }
+
+    /// Ask the server to pad responses with decoy suffixes (on by default)
+    pub fn padding(mut self, padding: bool) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+}
+
+impl Default for RangeClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BreachCheck for RangeClient {
+    fn count_sha1(&self, digest: &[u8; 20]) -> Result<u64, PassgenError> {
+        let hash = to_hex(digest);
+        let (prefix, suffix) = hash.split_at(5);
+        let url = format!("{}{}", self.base_url, prefix);
+
+        let mut request = self.agent.get(&url);
+        if self.padding {
+            request = request.set("Add-Padding", "true");
+        }
+        let body = request
+            .call()
+            .map_err(|err| PassgenError::Breach(err.to_string()))?
+            .into_string()
+            .map_err(|err| PassgenError::Breach(format!("{}: {}", url, err)))?;
+
+        for line in body.lines() {
+            let Some((candidate, count)) = line.trim().split_once(':') else {
+                continue;
+            };
+            if candidate.eq_ignore_ascii_case(suffix) {
+                return count.trim().parse().map_err(|_| {
+                    PassgenError::Breach(format!("{}: invalid count in \"{}\"", url, line))
+                });
+            }
+        }
+        Ok(0)
+    }
+}
@@ -0,0 +1,164 @@
+use clap::{ArgMatches, Args};
+use dialoguer::{Password, theme::ColorfulTheme};
+use passgen::{
+    AnalysisContext, AttackerModel, PassgenError, PasswordStrength, Profile, SecretString,
+    try_analyze_smart,
+};
+use std::fs::File;
+use std::io::{self, BufReader, IsTerminal};
+use std::path::PathBuf;
+
+use super::{BreachArgs, ConfigArgs, FormatArgs, OutputArgs, config, read_lines};
+
+#[derive(Args, Debug, Clone)]
+pub struct AnalyzeArgs {
+    /// Passwords to analyze (visible in process listings and shell history; prefer --file or stdin)
+    #[arg(value_name = "PASSWORD", conflicts_with_all = ["file", "prompt"])]
+    pub passwords: Vec<SecretString>,
+
+    /// Read passwords from a file, one per line ("-" for stdin)
+    #[arg(short = 'f', long, value_name = "PATH", conflicts_with = "prompt")]
+    pub file: Option<PathBuf>,
+
+    /// Prompt for a password without echoing it (the default when stdin is a terminal)
+    #[arg(long)]
+    pub prompt: bool,
+
+    /// Words about the account owner that make a password weak, e.g. "jane,jdoe@acme.com,Acme"
+    #[arg(long, value_name = "WORDS", value_delimiter = ',')]
+    pub context: Vec<String>,
+
+    /// File of banned terms (one per line) to penalize and report
+    #[arg(long, value_name = "PATH")]
+    pub banned_words: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub format: FormatArgs,
+
+    /// Show detailed analysis
+    #[arg(short = 'd', long)]
+    pub detailed: bool,
+
+    /// Attacker for crack times: a built-in scenario or NAME=GUESSES_PER_SECOND (repeatable; the first is the headline)
+    #[arg(long = "attacker", value_name = "NAME[=RATE]")]
+    pub attackers: Vec<AttackerModel>,
+
+    #[command(flatten)]
+    pub breach: BreachArgs,
+
+    /// Leave passwords out of the output, reporting only their analysis
+    #[arg(long)]
+    pub omit_password: bool,
+
+    #[command(flatten)]
+    pub config: ConfigArgs,
+
+    #[cfg(feature = "parallel")]
+    #[command(flatten)]
+    pub parallel: super::ParallelArgs,
+}
+
+impl AnalyzeArgs {
+    pub fn apply_profile(&mut self, profile: &Profile, matches: &ArgMatches) {
+        config::fill(matches, "context", &mut self.context, &profile.user_inputs);
+        config::fill_some(
+            matches,
+            "banned_words",
+            &mut self.banned_words,
+            &profile.banned_words,
+        );
+        self.format.apply_profile(profile, matches);
+        config::fill(matches, "detailed", &mut self.detailed, &profile.detailed);
+        config::fill(
+            matches,
+            "attackers",
+            &mut self.attackers,
+            &profile.attackers,
+        );
+    }
+}
+
+/// Returns whether every password is absent from the breach sources
+pub fn run(mut args: AnalyzeArgs) -> Result<bool, PassgenError> {
+    let passwords = read_passwords(&mut args).unwrap_or_else(|err| {
+        eprintln!("Error: failed to read passwords: {}", err);
+        std::process::exit(1);
+    });
+    if passwords.is_empty() {
+        eprintln!("Error: no passwords to analyze");
+        std::process::exit(1);
+    }
+
+    let sources = args.breach.sources()?;
+    let count = passwords.len();
+    let mut breached = false;
+    let results = analyze_all(passwords, &args).map(|result| {
+        let mut strength = result?;
+        if !args.attackers.is_empty() {
+            strength.apply_attackers(&args.attackers);
+        }
+        super::check_breaches(&mut strength, &sources)?;
+        breached |= strength.breach_count.unwrap_or(0) > 0;
+        if args.omit_password {
+            strength.omit_password();
+        }
+        Ok(strength)
+    });
+
+    let output = OutputArgs {
+        count,
+        unique: false,
+        strength: true,
+        format: args.format.clone(),
+        detailed: args.detailed,
+        attackers: args.attackers.clone(),
+        breach: args.breach.clone(),
+        reject_breached: false,
+        #[cfg(feature = "parallel")]
+        parallel: args.parallel.clone(),
+    };
+    super::print_results(results, &output, "Analyzed", None)?;
+    Ok(!breached)
+}
+
+/// Analyze each password, on the threads asked for with --jobs; breach
+/// lookups stay on the main thread
+fn analyze_all(
+    passwords: Vec<SecretString>,
+    args: &AnalyzeArgs,
+) -> Box<dyn Iterator<Item = Result<PasswordStrength, PassgenError>>> {
+    let context = AnalysisContext {
+        user_inputs: args.context.clone(),
+        banned_words: args.banned_words.as_ref().map(super::load_banned_words),
+    };
+    #[cfg(feature = "parallel")]
+    if let Some(parallelism) = args.parallel.parallelism() {
+        return Box::new(passgen::analyze_parallel(passwords, context, parallelism));
+    }
+    Box::new(
+        passwords
+            .into_iter()
+            .map(move |password| try_analyze_smart(&password, &context)),
+    )
+}
+
+/// Collect passwords from arguments, a file, stdin or a hidden prompt.
+/// Arguments are moved out of `args` so no second copy outlives the call.
+fn read_passwords(args: &mut AnalyzeArgs) -> io::Result<Vec<SecretString>> {
+    if !args.passwords.is_empty() {
+        return Ok(std::mem::take(&mut args.passwords));
+    }
+
+    match &args.file {
+        Some(path) if path.as_os_str() != "-" => read_lines(BufReader::new(File::open(path)?)),
+        Some(_) => read_lines(io::stdin().lock()),
+        None if args.prompt || io::stdin().is_terminal() => {
+            let password = Password::with_theme(&ColorfulTheme::default())
+                .with_prompt("Password to analyze")
+                .interact()
+                .map_err(io::Error::other)?;
+            Ok(vec![SecretString::from(password)])
+        }
+        None => read_lines(io::stdin().lock()),
+    }
+}
@@ -0,0 +1,33 @@
+use clap::Args;
+use passgen::{PassgenError, build_index};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+
+#[derive(Args, Debug, Clone)]
+pub struct BreachIndexArgs {
+    /// Pwned Passwords SHA-1 dump ordered by hash ("-" for stdin)
+    #[arg(value_name = "DUMP")]
+    pub dump: PathBuf,
+
+    /// Where to write the index
+    #[arg(value_name = "OUTPUT")]
+    pub output: PathBuf,
+}
+
+pub fn run(args: BreachIndexArgs) -> Result<(), PassgenError> {
+    let io_error = |path: &PathBuf, err: std::io::Error| {
+        PassgenError::Breach(format!("{}: {}", path.display(), err))
+    };
+    let out = File::create(&args.output).map_err(|err| io_error(&args.output, err))?;
+    let out = BufWriter::new(out);
+
+    let records = if args.dump.as_os_str() == "-" {
+        build_index(std::io::stdin().lock(), out)?
+    } else {
+        let dump = File::open(&args.dump).map_err(|err| io_error(&args.dump, err))?;
+        build_index(BufReader::new(dump), out)?
+    };
+    eprintln!("Indexed {} hashes into {}", records, args.output.display());
+    Ok(())
+}
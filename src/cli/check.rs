@@ -0,0 +1,154 @@
+use clap::Args;
+use passgen::{
+    OutputFormat, PassgenError, PasswordPolicy, PolicyViolation, SecretString, check_policy,
+};
+use serde::Serialize;
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use zeroize::Zeroizing;
+
+use super::{FormatArgs, format};
+
+#[derive(Args, Debug, Clone)]
+pub struct CheckArgs {
+    /// Policy file (TOML, or JSON by extension) to check against
+    #[arg(long, value_name = "PATH")]
+    pub policy: PathBuf,
+
+    /// Passwords to check; read from stdin, one per line, when omitted
+    #[arg(value_name = "PASSWORD")]
+    pub passwords: Vec<SecretString>,
+
+    #[command(flatten)]
+    pub format: FormatArgs,
+}
+
+#[derive(Serialize)]
+struct CheckResult<'a> {
+    password: &'a str,
+    compliant: bool,
+    violations: Vec<PolicyViolation>,
+}
+
+/// TOML needs a table at the top level
+#[derive(Serialize)]
+struct CheckReport<'a> {
+    results: &'a [CheckResult<'a>],
+}
+
+/// A [`CheckResult`] as one CSV row
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    password: &'a str,
+    compliant: bool,
+    violations: String,
+}
+
+/// Returns whether every password satisfies the policy
+pub fn run(args: CheckArgs) -> Result<bool, PassgenError> {
+    let policy = PasswordPolicy::from_file(&args.policy)?;
+    let passwords = if args.passwords.is_empty() {
+        super::read_lines(io::stdin().lock()).unwrap_or_else(|err| {
+            eprintln!("Error: failed to read stdin: {}", err);
+            std::process::exit(1);
+        })
+    } else {
+        args.passwords
+    };
+
+    let results: Vec<CheckResult> = passwords
+        .iter()
+        .map(|password| {
+            let violations = check_policy(password, &policy);
+            CheckResult {
+                password: password.expose_secret(),
+                compliant: violations.is_empty(),
+                violations,
+            }
+        })
+        .collect();
+
+    let mut out = io::stdout().lock();
+    super::exit_on_write_error(write_results(&mut out, &results, args.format.format()));
+
+    Ok(results.iter().all(|result| result.compliant))
+}
+
+fn write_results(
+    out: &mut impl Write,
+    results: &[CheckResult],
+    format: OutputFormat,
+) -> io::Result<()> {
+    // Text rendered before writing holds every password, so it is wiped after
+    let text = match format {
+        OutputFormat::Text => {
+            for result in results {
+                let mark = if result.compliant { "✅" } else { "❌" };
+                writeln!(out, "{} {}", mark, result.password)?;
+                for violation in &result.violations {
+                    writeln!(out, "  - {}", violation)?;
+                }
+            }
+            return Ok(());
+        }
+        OutputFormat::Table => {
+            let rows: Vec<Vec<Cow<str>>> = results
+                .iter()
+                .enumerate()
+                .map(|(i, result)| {
+                    vec![
+                        Cow::Owned((i + 1).to_string()),
+                        Cow::Borrowed(result.password),
+                        Cow::Borrowed(if result.compliant { "yes" } else { "no" }),
+                        Cow::Owned(violations(result)),
+                    ]
+                })
+                .collect();
+            let header = ["#", "Password", "Compliant", "Violations"];
+            return format::write_aligned(out, &header, &rows);
+        }
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, results)?;
+            return writeln!(out);
+        }
+        OutputFormat::Ndjson => {
+            for result in results {
+                serde_json::to_writer(&mut *out, result)?;
+                writeln!(out)?;
+            }
+            return Ok(());
+        }
+        OutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            for result in results {
+                let row = CsvRow {
+                    password: result.password,
+                    compliant: result.compliant,
+                    violations: violations(result),
+                };
+                writer.serialize(row).map_err(format::csv_error)?;
+            }
+            return writer.flush();
+        }
+        OutputFormat::Null => {
+            // Only the compliant passwords, ready for the next command
+            for result in results.iter().filter(|result| result.compliant) {
+                out.write_all(result.password.as_bytes())?;
+                out.write_all(b"\0")?;
+            }
+            return Ok(());
+        }
+        OutputFormat::Yaml => serde_yaml::to_string(results).map_err(io::Error::other)?,
+        OutputFormat::Toml => {
+            toml::to_string(&CheckReport { results }).map_err(io::Error::other)?
+        }
+    };
+    out.write_all(Zeroizing::new(text).as_bytes())
+}
+
+/// The broken rules, separated by semicolons
+fn violations(result: &CheckResult) -> String {
+    let violations: Vec<String> = result.violations.iter().map(ToString::to_string).collect();
+    violations.join("; ")
+}
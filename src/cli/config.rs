@@ -0,0 +1,53 @@
+use clap::ArgMatches;
+use clap::parser::ValueSource;
+use passgen::{Config, PassgenError, Profile};
+use std::path::Path;
+
+/// Settings from `--config` (or the discovered config file) and `--profile`;
+/// empty when there is no config file and no profile was asked for
+pub fn load_profile(config: Option<&Path>, profile: Option<&str>) -> Result<Profile, PassgenError> {
+    let config = match config {
+        Some(path) => Some(Config::from_file(path)?),
+        None => Config::discover()?,
+    };
+    match (config, profile) {
+        (Some(config), profile) => config.profile(profile),
+        (None, Some(profile)) => Err(PassgenError::Config(format!(
+            "profile '{}' requested but no config file was found (looked for {})",
+            profile,
+            Config::default_path()
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| "$PASSGEN_CONFIG".to_string())
+        ))),
+        (None, None) => Ok(Profile::default()),
+    }
+}
+
+/// Whether the argument `id` was given on the command line rather than
+/// left at its default
+pub fn given(matches: &ArgMatches, id: &str) -> bool {
+    matches
+        .value_source(id)
+        .is_some_and(|source| source != ValueSource::DefaultValue)
+}
+
+/// Take the profile's `value` for `field` unless `id` was given on the command line
+pub fn fill<T: Clone>(matches: &ArgMatches, id: &str, field: &mut T, value: &Option<T>) {
+    if let Some(value) = value
+        && !given(matches, id)
+    {
+        *field = value.clone();
+    }
+}
+
+/// [`fill`] for arguments that are optional on the command line
+pub fn fill_some<T: Clone>(
+    matches: &ArgMatches,
+    id: &str,
+    field: &mut Option<T>,
+    value: &Option<T>,
+) {
+    if value.is_some() && !given(matches, id) {
+        *field = value.clone();
+    }
+}
@@ -0,0 +1,166 @@
+use passgen::{OutputFormat, PasswordStrength};
+use serde::Serialize;
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+/// Writer for the formats printed one password at a time
+pub enum Stream<W: Write> {
+    Ndjson(W),
+    Csv(Box<csv::Writer<W>>),
+    Null(W),
+}
+
+/// The scalar fields of a [`PasswordStrength`], as one CSV row
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    password: Option<&'a str>,
+    entropy_bits: f64,
+    strength_score: u8,
+    strength_label: &'a str,
+    crack_time_seconds: f64,
+    crack_time_display: &'a str,
+    guesses_log10: f64,
+    generator_entropy_bits: Option<f64>,
+    character_sets: String,
+    breach_count: Option<u64>,
+    banned_word: Option<&'a str>,
+    warning: Option<&'a str>,
+}
+
+impl<W: Write> Stream<W> {
+    /// `format` must be one of the [streamed](OutputFormat::is_streamed) formats
+    pub fn new(out: W, format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Ndjson => Stream::Ndjson(out),
+            OutputFormat::Csv => Stream::Csv(Box::new(csv::Writer::from_writer(out))),
+            OutputFormat::Null => Stream::Null(out),
+            other => unreachable!("{} is not a streamed format", other),
+        }
+    }
+
+    /// Write one password and flush it, so consumers see it straight away
+    pub fn write(&mut self, strength: &PasswordStrength) -> io::Result<()> {
+        match self {
+            Stream::Ndjson(out) => {
+                serde_json::to_writer(&mut *out, strength)?;
+                writeln!(out)?;
+                out.flush()
+            }
+            Stream::Csv(writer) => {
+                let row = CsvRow {
+                    password: strength.password.as_deref(),
+                    entropy_bits: strength.entropy_bits,
+                    strength_score: strength.strength_score,
+                    strength_label: &strength.strength_label,
+                    crack_time_seconds: strength.crack_time_seconds,
+                    crack_time_display: &strength.crack_time_display,
+                    guesses_log10: strength.guesses_log10,
+                    generator_entropy_bits: strength.generator_entropy_bits,
+                    character_sets: strength.character_sets.join(";"),
+                    breach_count: strength.breach_count,
+                    banned_word: strength.banned_word.as_deref(),
+                    warning: strength.warning.as_deref(),
+                };
+                writer.serialize(row).map_err(csv_error)?;
+                writer.flush()
+            }
+            Stream::Null(out) => {
+                if let Some(password) = &strength.password {
+                    out.write_all(password.as_bytes())?;
+                }
+                out.write_all(b"\0")?;
+                out.flush()
+            }
+        }
+    }
+}
+
+/// One aligned row per password; the breach column only appears when a
+/// breach source was checked
+pub fn write_table(out: &mut impl Write, passwords: &[PasswordStrength]) -> io::Result<()> {
+    let breaches = passwords.iter().any(|p| p.breach_count.is_some());
+    let mut header = vec![
+        "#",
+        "Password",
+        "Entropy",
+        "Score",
+        "Strength",
+        "Crack time",
+    ];
+    if breaches {
+        header.push("Breaches");
+    }
+
+    // Passwords are borrowed, never copied into the table
+    let rows: Vec<Vec<Cow<str>>> = passwords
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let mut row = vec![
+                Cow::Owned((i + 1).to_string()),
+                Cow::Borrowed(super::shown(p)),
+                Cow::Owned(format!("{:.1}", p.entropy_bits)),
+                Cow::Owned(format!("{}/4", p.strength_score)),
+                Cow::Borrowed(p.strength_label.as_str()),
+                Cow::Borrowed(p.crack_time_display.as_str()),
+            ];
+            if breaches {
+                row.push(Cow::Owned(
+                    p.breach_count.map(|c| c.to_string()).unwrap_or_default(),
+                ));
+            }
+            row
+        })
+        .collect();
+
+    write_aligned(out, &header, &rows)
+}
+
+/// `header` over a rule and `rows`, each column as wide as its widest cell
+pub fn write_aligned(
+    out: &mut impl Write,
+    header: &[&str],
+    rows: &[Vec<Cow<str>>],
+) -> io::Result<()> {
+    let widths: Vec<usize> = header
+        .iter()
+        .enumerate()
+        .map(|(col, title)| {
+            rows.iter()
+                .map(|row| row[col].chars().count())
+                .fold(title.len(), usize::max)
+        })
+        .collect();
+    let rule: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+
+    write_row(out, header, &widths)?;
+    write_row(out, &rule, &widths)?;
+    for row in rows {
+        write_row(out, row, &widths)?;
+    }
+    Ok(())
+}
+
+/// The I/O error behind a CSV error, so a closed pipe is still recognized
+pub fn csv_error(err: csv::Error) -> io::Error {
+    match err.into_kind() {
+        csv::ErrorKind::Io(err) => err,
+        other => io::Error::other(format!("{:?}", other)),
+    }
+}
+
+/// Written cell by cell, without padding after the last non-empty one
+fn write_row(out: &mut impl Write, cells: &[impl AsRef<str>], widths: &[usize]) -> io::Result<()> {
+    let last = cells
+        .iter()
+        .rposition(|cell| !cell.as_ref().is_empty())
+        .unwrap_or(0);
+    for (i, (cell, &width)) in cells.iter().zip(widths).enumerate().take(last + 1) {
+        if i == last {
+            write!(out, "{}", cell.as_ref())?;
+        } else {
+            write!(out, "{:<width$}  ", cell.as_ref())?;
+        }
+    }
+    writeln!(out)
+}
@@ -0,0 +1,498 @@
+use clap::error::ErrorKind;
+use clap::{ArgMatches, Args, CommandFactory};
+use dialoguer::{Confirm, Input, MultiSelect, theme::ColorfulTheme};
+use passgen::{
+    Mode, PassgenError, PasswordGenerator, PasswordOptions, PasswordPolicy, Profile,
+    SmartPasswordMode,
+};
+use std::path::PathBuf;
+
+use super::config::{fill, fill_some};
+use super::{Cli, ConfigArgs, OutputArgs, PassphraseArgs, config, phrase};
+
+/// Character-set options that only apply to the default random mode
+const CONSTRAINT_ARGS: [&str; 10] = [
+    "charsets",
+    "min_uppercase",
+    "min_lowercase",
+    "min_numbers",
+    "min_special",
+    "max_uppercase",
+    "max_lowercase",
+    "max_numbers",
+    "max_special",
+    "policy",
+];
+
+/// Further options `--phonetic`, `--pattern` and `--passphrase` reject, on
+/// top of [`CONSTRAINT_ARGS`]
+const PHONETIC_ARGS: [&str; 1] = ["lowercase"];
+const PATTERN_ARGS: [&str; 5] = ["length", "uppercase", "lowercase", "numbers", "special"];
+const PASSPHRASE_ARGS: [&str; 6] = [
+    "length",
+    "uppercase",
+    "lowercase",
+    "avoid_ambiguous",
+    "symbols",
+    "exclude_chars",
+];
+
+#[derive(Args, Debug, Clone)]
+pub struct GenerateArgs {
+    /// Length of the password
+    #[arg(short, long, default_value_t = 16)]
+    pub length: usize,
+
+    /// Include uppercase letters
+    #[arg(short = 'u', long)]
+    pub uppercase: bool,
+
+    /// Include lowercase letters
+    #[arg(short = 'w', long)]
+    pub lowercase: bool,
+
+    /// Include numbers
+    #[arg(short = 'n', long)]
+    pub numbers: bool,
+
+    /// Include special characters
+    #[arg(short = 's', long)]
+    pub special: bool,
+
+    /// Exclude ambiguous characters like 1, l, I, 0, O
+    #[arg(short = 'a', long)]
+    pub avoid_ambiguous: bool,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    /// Interactive password building wizard
+    #[arg(
+        short = 'i',
+        long,
+        conflicts_with_all = ["length", "uppercase", "lowercase", "numbers", "special", "avoid_ambiguous"],
+    )]
+    pub interactive: bool,
+
+    /// Generate phonetic password (easier to remember; always lowercase, -u adds capitals)
+    #[arg(
+        long,
+        conflicts_with_all = CONSTRAINT_ARGS,
+        conflicts_with_all = PHONETIC_ARGS,
+        conflicts_with = "interactive",
+    )]
+    pub phonetic: bool,
+
+    /// Generate password from a pattern such as "U{3}D{4}" or "[A-Z0-9]{4}('-'[A-Z0-9]{4}){2}"
+    #[arg(
+        short = 'p',
+        long,
+        value_name = "PATTERN",
+        conflicts_with_all = CONSTRAINT_ARGS,
+        conflicts_with_all = PATTERN_ARGS,
+        conflicts_with_all = ["interactive", "phonetic"],
+    )]
+    pub pattern: Option<String>,
+
+    /// Use a custom symbol set instead of the default special characters (implies -s)
+    #[arg(long, value_name = "SET")]
+    pub symbols: Option<String>,
+
+    /// Add a custom character set; at least one character is drawn from each (repeatable)
+    #[arg(long = "charset", value_name = "SET")]
+    pub charsets: Vec<String>,
+
+    /// Characters that must never appear in the password
+    #[arg(long, value_name = "CHARS", default_value = "")]
+    pub exclude_chars: String,
+
+    /// Minimum number of uppercase letters
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub min_uppercase: usize,
+
+    /// Minimum number of lowercase letters
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub min_lowercase: usize,
+
+    /// Minimum number of digits
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub min_numbers: usize,
+
+    /// Minimum number of special characters
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub min_special: usize,
+
+    /// Maximum number of uppercase letters
+    #[arg(long, value_name = "N")]
+    pub max_uppercase: Option<usize>,
+
+    /// Maximum number of lowercase letters
+    #[arg(long, value_name = "N")]
+    pub max_lowercase: Option<usize>,
+
+    /// Maximum number of digits
+    #[arg(long, value_name = "N")]
+    pub max_numbers: Option<usize>,
+
+    /// Maximum number of special characters
+    #[arg(long, value_name = "N")]
+    pub max_special: Option<usize>,
+
+    /// Policy file (TOML, or JSON by extension) the password must satisfy
+    #[arg(long, value_name = "PATH")]
+    pub policy: Option<PathBuf>,
+
+    /// File of terms (one per line) the password must not contain, even with l33t substitutions
+    #[arg(long, value_name = "PATH")]
+    pub banned_words: Option<PathBuf>,
+
+    /// Generate a diceware-style passphrase (-n/-s inject a digit/symbol); same as `passgen phrase`
+    #[arg(
+        long,
+        conflicts_with_all = CONSTRAINT_ARGS,
+        conflicts_with_all = PASSPHRASE_ARGS,
+        conflicts_with_all = ["interactive", "phonetic", "pattern"],
+    )]
+    pub passphrase: bool,
+
+    #[command(flatten)]
+    pub phrase: PassphraseArgs,
+
+    #[command(flatten)]
+    pub config: ConfigArgs,
+
+    /// Seed the random generator for reproducible output (testing only: seeded passwords are predictable)
+    #[arg(long, value_name = "N")]
+    pub seed: Option<u64>,
+
+    /// Words about the account owner, from the config file's `user_inputs`
+    #[arg(skip)]
+    pub user_inputs: Vec<String>,
+}
+
+impl GenerateArgs {
+    /// Fill in every option not given on the command line from `profile`.
+    ///
+    /// Character classes and modes are taken as a whole: `passgen -n` under a
+    /// profile with `uppercase = true` gives digits only, and `--phonetic`
+    /// replaces the profile's mode rather than clashing with it. Options the
+    /// profile's mode rejects are a usage error, as they are with the mode's
+    /// own flag.
+    pub fn apply_profile(&mut self, profile: &Profile, matches: &ArgMatches) {
+        let given = |id| config::given(matches, id);
+
+        fill(matches, "length", &mut self.length, &profile.length);
+        if !["uppercase", "lowercase", "numbers", "special", "charsets"]
+            .into_iter()
+            .any(given)
+        {
+            fill(
+                matches,
+                "uppercase",
+                &mut self.uppercase,
+                &profile.uppercase,
+            );
+            fill(
+                matches,
+                "lowercase",
+                &mut self.lowercase,
+                &profile.lowercase,
+            );
+            fill(matches, "numbers", &mut self.numbers, &profile.numbers);
+            fill(matches, "special", &mut self.special, &profile.special);
+            fill(
+                matches,
+                "charsets",
+                &mut self.charsets,
+                &profile.custom_sets,
+            );
+        }
+        fill(
+            matches,
+            "avoid_ambiguous",
+            &mut self.avoid_ambiguous,
+            &profile.avoid_ambiguous,
+        );
+        fill_some(matches, "symbols", &mut self.symbols, &profile.symbols);
+        fill(
+            matches,
+            "exclude_chars",
+            &mut self.exclude_chars,
+            &profile.exclude_chars,
+        );
+        fill(
+            matches,
+            "min_uppercase",
+            &mut self.min_uppercase,
+            &profile.min_uppercase,
+        );
+        fill(
+            matches,
+            "min_lowercase",
+            &mut self.min_lowercase,
+            &profile.min_lowercase,
+        );
+        fill(
+            matches,
+            "min_numbers",
+            &mut self.min_numbers,
+            &profile.min_numbers,
+        );
+        fill(
+            matches,
+            "min_special",
+            &mut self.min_special,
+            &profile.min_special,
+        );
+        fill_some(
+            matches,
+            "max_uppercase",
+            &mut self.max_uppercase,
+            &profile.max_uppercase,
+        );
+        fill_some(
+            matches,
+            "max_lowercase",
+            &mut self.max_lowercase,
+            &profile.max_lowercase,
+        );
+        fill_some(
+            matches,
+            "max_numbers",
+            &mut self.max_numbers,
+            &profile.max_numbers,
+        );
+        fill_some(
+            matches,
+            "max_special",
+            &mut self.max_special,
+            &profile.max_special,
+        );
+        fill_some(matches, "policy", &mut self.policy, &profile.policy);
+        fill_some(
+            matches,
+            "banned_words",
+            &mut self.banned_words,
+            &profile.banned_words,
+        );
+        if let Some(user_inputs) = &profile.user_inputs {
+            self.user_inputs = user_inputs.clone();
+        }
+
+        if !["interactive", "phonetic", "pattern", "passphrase"]
+            .into_iter()
+            .any(given)
+        {
+            let mode: Option<(&str, &[&str])> = match profile.mode() {
+                Some(Mode::Phonetic) => {
+                    self.phonetic = true;
+                    Some(("phonetic", &PHONETIC_ARGS))
+                }
+                Some(Mode::Pattern) => {
+                    self.pattern = profile.pattern.clone();
+                    Some(("pattern", &PATTERN_ARGS))
+                }
+                Some(Mode::Passphrase) => {
+                    self.passphrase = true;
+                    Some(("passphrase", &PASSPHRASE_ARGS))
+                }
+                Some(Mode::Random) | None => None,
+            };
+            if let Some((mode, rejected)) = mode
+                && let Some(id) = CONSTRAINT_ARGS.iter().chain(rejected).find(|id| given(id))
+            {
+                Cli::command()
+                    .error(
+                        ErrorKind::ArgumentConflict,
+                        format!(
+                            "{} cannot be used with the profile's {} mode",
+                            flag_name(id),
+                            mode
+                        ),
+                    )
+                    .exit();
+            }
+        }
+        // Passphrase settings in the defaults must not trip the "requires
+        // --passphrase" check in other modes
+        if self.passphrase {
+            self.phrase.apply_profile(profile, matches);
+        }
+        self.output.apply_profile(profile, matches);
+    }
+}
+
+/// The `--long` form of the argument `id`, for error messages
+fn flag_name(id: &str) -> String {
+    Cli::command()
+        .get_arguments()
+        .find(|arg| arg.get_id() == id)
+        .and_then(|arg| arg.get_long())
+        .map_or_else(|| id.to_string(), |long| format!("--{}", long))
+}
+
+pub fn run(mut args: GenerateArgs) -> Result<(), PassgenError> {
+    if args.passphrase {
+        let phrase_args = phrase::PhraseArgs {
+            phrase: args.phrase,
+            digit: args.numbers,
+            symbol: args.special,
+            banned_words: args.banned_words,
+            output: args.output,
+            seed: args.seed,
+            config: args.config,
+            user_inputs: args.user_inputs,
+        };
+        return phrase::run(phrase_args);
+    }
+    if args.phrase.is_set() {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--words, --separator, --capitalize and --wordlist require --passphrase",
+            )
+            .exit();
+    }
+
+    let rng = super::rng(args.seed);
+
+    // Handle smart password generation modes
+    let mode = if args.phonetic {
+        Some(SmartPasswordMode::Phonetic)
+    } else {
+        args.pattern.clone().map(SmartPasswordMode::Pattern)
+    };
+    if let Some(mode) = mode {
+        let generator = PasswordGenerator::smart_with_rng(mode, password_options(&args), rng)?;
+        return super::generate_and_print(&args.output, generator);
+    }
+
+    let opts = if args.interactive {
+        let wizard = run_interactive_wizard();
+        PasswordOptions {
+            length: wizard.length,
+            uppercase: wizard.uppercase,
+            lowercase: wizard.lowercase,
+            numbers: wizard.numbers,
+            special: wizard.special || args.symbols.is_some(),
+            avoid_ambiguous: wizard.avoid_ambiguous,
+            ..password_options(&args)
+        }
+    } else {
+        // Set default character sets if none specified
+        if !args.uppercase
+            && !args.lowercase
+            && !args.numbers
+            && !args.special
+            && args.charsets.is_empty()
+        {
+            args.uppercase = true;
+            args.lowercase = true;
+            args.numbers = true;
+            args.special = true;
+        }
+        password_options(&args)
+    };
+
+    let generator = PasswordGenerator::with_rng(opts, rng)?;
+    super::generate_and_print(&args.output, generator)
+}
+
+fn password_options(args: &GenerateArgs) -> PasswordOptions {
+    PasswordOptions {
+        length: args.length,
+        uppercase: args.uppercase,
+        lowercase: args.lowercase,
+        numbers: args.numbers,
+        special: args.special || args.symbols.is_some(),
+        avoid_ambiguous: args.avoid_ambiguous,
+        symbols: args.symbols.clone(),
+        custom_sets: args.charsets.clone(),
+        exclude_chars: args.exclude_chars.clone(),
+        min_uppercase: args.min_uppercase,
+        min_lowercase: args.min_lowercase,
+        min_numbers: args.min_numbers,
+        min_special: args.min_special,
+        max_uppercase: args.max_uppercase,
+        max_lowercase: args.max_lowercase,
+        max_numbers: args.max_numbers,
+        max_special: args.max_special,
+        policy: args
+            .policy
+            .as_ref()
+            .map(|path| PasswordPolicy::from_file(path).unwrap_or_else(|e| crate::fail(e))),
+        user_inputs: args.user_inputs.clone(),
+        banned_words: args.banned_words.as_ref().map(super::load_banned_words),
+    }
+}
+
+fn run_interactive_wizard() -> PasswordOptions {
+    println!("🔐 Welcome to PassGen Interactive Mode!");
+    println!("Let's build your perfect password together.\n");
+
+    // Get password length
+    let length: usize = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Password length")
+        .default(16)
+        .interact_text()
+        .unwrap();
+
+    // Select character sets
+    let character_sets = &[
+        "Uppercase letters (A-Z)",
+        "Lowercase letters (a-z)",
+        "Numbers (0-9)",
+        "Special characters (!@#$%^&*)",
+    ];
+
+    let defaults = &[true, true, true, true];
+    let selected_sets: Vec<usize> = MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Select character sets to include")
+        .items(character_sets)
+        .defaults(defaults)
+        .interact()
+        .unwrap();
+
+    // Parse selections
+    let uppercase = selected_sets.contains(&0);
+    let lowercase = selected_sets.contains(&1);
+    let numbers = selected_sets.contains(&2);
+    let special = selected_sets.contains(&3);
+
+    // Ask about ambiguous characters
+    let avoid_ambiguous = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Exclude ambiguous characters? (1, l, I, 0, O)")
+        .default(true)
+        .interact()
+        .unwrap();
+
+    println!("\n📋 Configuration Summary:");
+    println!("   Length: {}", length);
+    println!(
+        "   Character sets: {}",
+        [
+            if uppercase { Some("Uppercase") } else { None },
+            if lowercase { Some("Lowercase") } else { None },
+            if numbers { Some("Numbers") } else { None },
+            if special { Some("Special") } else { None },
+        ]
+        .iter()
+        .filter_map(|&x| x)
+        .collect::<Vec<_>>()
+        .join(", ")
+    );
+    println!(
+        "   Avoid ambiguous: {}",
+        if avoid_ambiguous { "Yes" } else { "No" }
+    );
+
+    PasswordOptions {
+        length,
+        uppercase,
+        lowercase,
+        numbers,
+        special,
+        avoid_ambiguous,
+        ..Default::default()
+    }
+}
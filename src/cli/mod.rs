@@ -0,0 +1,716 @@
+use clap::{ArgMatches, Args, Parser, Subcommand};
+use passgen::{
+    AnalysisSummary, AttackerModel, BannedWords, BreachCheck, BreachCorpus, Capitalization,
+    OutputFormat, PassgenError, PasswordAnalysis, PasswordGenerator, PasswordStrength, Profile,
+    SecretString, collision_probability,
+};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use zeroize::Zeroizing;
+
+pub mod analyze;
+pub mod breach_index;
+pub mod check;
+pub mod config;
+mod format;
+pub mod generate;
+pub mod phrase;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Options for `generate`, which runs when no subcommand is given
+    #[command(flatten)]
+    pub generate: generate::GenerateArgs,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Generate passwords (the default when no subcommand is given)
+    Generate(Box<generate::GenerateArgs>),
+    /// Generate diceware-style passphrases
+    Phrase(phrase::PhraseArgs),
+    /// Analyze the strength of existing passwords
+    ///
+    /// Exits with 15 if a breach check (--breach-corpus, --hibp) finds any of
+    /// the passwords.
+    Analyze(analyze::AnalyzeArgs),
+    /// Check existing passwords against a policy file
+    Check(check::CheckArgs),
+    /// Convert a sorted Pwned Passwords dump into a compact binary index
+    BreachIndex(breach_index::BreachIndexArgs),
+}
+
+/// Config file flags for the commands that read one
+#[derive(Args, Debug, Clone)]
+pub struct ConfigArgs {
+    /// Config file with defaults and profiles [default: $PASSGEN_CONFIG or ~/.config/passgen/config.toml]
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Apply a [profile.NAME] table from the config file; command-line options still win
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+}
+
+impl ConfigArgs {
+    /// The selected profile, or the config file's defaults
+    pub fn load(&self) -> Result<Profile, PassgenError> {
+        config::load_profile(self.config.as_deref(), self.profile.as_deref())
+    }
+}
+
+/// Output flags shared by every mode
+#[derive(Args, Debug, Clone)]
+pub struct OutputArgs {
+    /// Number of passwords to generate
+    #[arg(short, long, default_value_t = 1)]
+    pub count: usize,
+
+    /// Never print the same password twice (fails if the options allow fewer than --count)
+    #[arg(long)]
+    pub unique: bool,
+
+    /// Show password strength analysis
+    #[arg(short = 't', long)]
+    pub strength: bool,
+
+    #[command(flatten)]
+    pub format: FormatArgs,
+
+    /// Show detailed analysis (implies --strength)
+    #[arg(short = 'd', long)]
+    pub detailed: bool,
+
+    /// Attacker for crack times: a built-in scenario or NAME=GUESSES_PER_SECOND (repeatable; the first is the headline)
+    #[arg(long = "attacker", value_name = "NAME[=RATE]")]
+    pub attackers: Vec<AttackerModel>,
+
+    #[command(flatten)]
+    pub breach: BreachArgs,
+
+    /// Regenerate passwords that appear in the breach corpus or range API
+    #[arg(long, requires = "BreachArgs")]
+    pub reject_breached: bool,
+
+    #[cfg(feature = "parallel")]
+    #[command(flatten)]
+    pub parallel: ParallelArgs,
+}
+
+/// Output format flags shared by every mode
+#[derive(Args, Debug, Clone)]
+pub struct FormatArgs {
+    /// Output format: text, table, json, ndjson, csv, yaml, toml or null [default: text]
+    #[arg(long, value_name = "FORMAT")]
+    pub format: Option<OutputFormat>,
+
+    /// Output in JSON format (same as --format json)
+    #[arg(short = 'j', long, conflicts_with = "format")]
+    pub json: bool,
+
+    /// Print bare passwords terminated by NUL bytes, for `xargs -0` (same as --format null)
+    #[arg(short = '0', long, conflicts_with_all = ["format", "json"])]
+    pub null: bool,
+}
+
+impl FormatArgs {
+    pub fn format(&self) -> OutputFormat {
+        if self.json {
+            OutputFormat::Json
+        } else if self.null {
+            OutputFormat::Null
+        } else {
+            self.format.unwrap_or_default()
+        }
+    }
+
+    pub fn apply_profile(&mut self, profile: &Profile, matches: &ArgMatches) {
+        if !["json", "null"]
+            .into_iter()
+            .any(|id| config::given(matches, id))
+        {
+            config::fill_some(matches, "format", &mut self.format, &profile.format);
+        }
+    }
+}
+
+/// Breached-password lookups shared by every mode
+#[derive(Args, Debug, Clone)]
+pub struct BreachArgs {
+    /// Report how often each password appears in a local Pwned Passwords dump or index
+    #[arg(long, value_name = "PATH")]
+    pub breach_corpus: Option<PathBuf>,
+
+    /// Look passwords up with the Pwned Passwords range API (only a 5-character hash prefix is sent)
+    #[cfg(feature = "hibp")]
+    #[arg(long)]
+    pub hibp: bool,
+
+    /// Base URL of a range API mirror, e.g. https://hibp.internal/range/ (implies --hibp)
+    #[cfg(feature = "hibp")]
+    #[arg(long, value_name = "URL")]
+    pub hibp_url: Option<String>,
+}
+
+/// Thread-pool flags for bulk generation and analysis
+#[cfg(feature = "parallel")]
+#[derive(Args, Debug, Clone)]
+pub struct ParallelArgs {
+    /// Generate or analyze on N threads (0 for one per CPU)
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    pub jobs: usize,
+
+    /// With --jobs, print results in generation or input order instead of as each is ready
+    #[arg(long)]
+    pub ordered: bool,
+}
+
+#[cfg(feature = "parallel")]
+impl ParallelArgs {
+    /// The thread pool to use, or None to stay on the main thread
+    pub fn parallelism(&self) -> Option<passgen::Parallelism> {
+        (self.jobs != 1).then_some(passgen::Parallelism {
+            jobs: self.jobs,
+            ordered: self.ordered,
+        })
+    }
+}
+
+impl OutputArgs {
+    pub fn apply_profile(&mut self, profile: &Profile, matches: &ArgMatches) {
+        config::fill(matches, "count", &mut self.count, &profile.count);
+        config::fill(matches, "unique", &mut self.unique, &profile.unique);
+        config::fill(matches, "strength", &mut self.strength, &profile.strength);
+        config::fill(matches, "detailed", &mut self.detailed, &profile.detailed);
+        self.format.apply_profile(profile, matches);
+        config::fill(
+            matches,
+            "attackers",
+            &mut self.attackers,
+            &profile.attackers,
+        );
+    }
+
+    /// Whether the output shows more than the passwords themselves: every
+    /// format but `null` does, except for a lone password in text without
+    /// --strength
+    pub fn needs_analysis(&self) -> bool {
+        match self.format.format() {
+            OutputFormat::Null => false,
+            OutputFormat::Text => self.strength || self.detailed || self.count > 1,
+            _ => true,
+        }
+    }
+}
+
+impl BreachArgs {
+    /// Open every breach source that was asked for
+    pub fn sources(&self) -> Result<Vec<Box<dyn BreachCheck>>, PassgenError> {
+        let mut sources: Vec<Box<dyn BreachCheck>> = Vec::new();
+        if let Some(path) = &self.breach_corpus {
+            sources.push(Box::new(BreachCorpus::open(path)?));
+        }
+        #[cfg(feature = "hibp")]
+        if let Some(url) = &self.hibp_url {
+            sources.push(Box::new(passgen::RangeClient::with_base_url(url)));
+        } else if self.hibp {
+            sources.push(Box::new(passgen::RangeClient::new()));
+        }
+        Ok(sources)
+    }
+}
+
+/// Passphrase options shared by `phrase` and `generate --passphrase`
+#[derive(Args, Debug, Clone)]
+pub struct PassphraseArgs {
+    /// Number of words in the passphrase
+    #[arg(long, value_name = "N")]
+    pub words: Option<usize>,
+
+    /// Separator placed between passphrase words [default: -]
+    #[arg(long)]
+    pub separator: Option<String>,
+
+    /// Passphrase capitalization (lower, upper, title, random) [default: lower]
+    #[arg(long, value_name = "STYLE")]
+    pub capitalize: Option<Capitalization>,
+
+    /// Wordlist file for passphrases (one word per line; EFF dice format accepted)
+    #[arg(long, value_name = "PATH")]
+    pub wordlist: Option<PathBuf>,
+}
+
+impl PassphraseArgs {
+    pub fn apply_profile(&mut self, profile: &Profile, matches: &ArgMatches) {
+        config::fill_some(matches, "words", &mut self.words, &profile.words);
+        config::fill_some(
+            matches,
+            "separator",
+            &mut self.separator,
+            &profile.separator,
+        );
+        config::fill_some(
+            matches,
+            "capitalize",
+            &mut self.capitalize,
+            &profile.capitalize,
+        );
+        config::fill_some(matches, "wordlist", &mut self.wordlist, &profile.wordlist);
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.words.is_some()
+            || self.separator.is_some()
+            || self.capitalize.is_some()
+            || self.wordlist.is_some()
+    }
+}
+
+/// One password per line; blank lines are skipped and line endings removed
+pub fn read_lines(reader: impl BufRead) -> io::Result<Vec<SecretString>> {
+    let mut passwords = Vec::new();
+    for line in reader.lines() {
+        let mut line = line?;
+        if line.ends_with('\r') {
+            line.pop();
+        }
+        // Moved rather than copied, so each line is wiped with its password
+        let password = SecretString::from(line);
+        if !password.is_empty() {
+            passwords.push(password);
+        }
+    }
+    Ok(passwords)
+}
+
+pub fn load_banned_words(path: &PathBuf) -> BannedWords {
+    BannedWords::from_file(path).unwrap_or_else(|err| {
+        eprintln!(
+            "Error: failed to load banned words {}: {}",
+            path.display(),
+            err
+        );
+        std::process::exit(1);
+    })
+}
+
+pub fn rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => {
+            eprintln!("Warning: --seed makes output predictable; never use seeded passwords");
+            StdRng::seed_from_u64(seed)
+        }
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// A random password showing up in a breach is vanishingly rare unless the
+/// options make the space tiny, so a bounded retry is enough
+const BREACH_ATTEMPTS: usize = 100;
+
+/// Print `output.count` passwords from `generator`, analyzed on the threads
+/// asked for with --jobs when the output shows any analysis
+pub fn generate_and_print(
+    output: &OutputArgs,
+    generator: PasswordGenerator,
+) -> Result<(), PassgenError> {
+    let collision = collision_probability(output.count, generator.entropy_bits());
+    let generator = if output.unique {
+        generator.unique(output.count)?
+    } else {
+        generator
+    };
+    if !output.needs_analysis() {
+        return print_bare(output, generator);
+    }
+    #[cfg(feature = "parallel")]
+    if let Some(parallelism) = output.parallel.parallelism() {
+        return print_generated(output, generator.parallel(parallelism), collision);
+    }
+    print_generated(output, generator.analyzed(), collision)
+}
+
+/// Print `output.count` results taken from the endless stream `passwords`,
+/// which repeat one with probability `collision`
+fn print_generated(
+    output: &OutputArgs,
+    passwords: impl Iterator<Item = Result<PasswordStrength, PassgenError>>,
+    collision: f64,
+) -> Result<(), PassgenError> {
+    let sources = output.breach.sources()?;
+    let mut passwords = passwords.map(|result| {
+        let mut strength = result?;
+        if !output.attackers.is_empty() {
+            strength.apply_attackers(&output.attackers);
+        }
+        check_breaches(&mut strength, &sources)?;
+        Ok(strength)
+    });
+    let rejected = |result: &Result<PasswordStrength, PassgenError>| {
+        output.reject_breached
+            && result
+                .as_ref()
+                .is_ok_and(|strength| strength.breach_count.unwrap_or(0) > 0)
+    };
+    let results = (0..output.count).map(|_| {
+        passwords
+            .by_ref()
+            .take(BREACH_ATTEMPTS)
+            .find(|result| !rejected(result))
+            .unwrap_or_else(|| {
+                Err(PassgenError::Breach(format!(
+                    "{} generated passwords in a row were breached; widen the options",
+                    BREACH_ATTEMPTS
+                )))
+            })
+    });
+    print_results(results, output, "Generated", Some(collision))
+}
+
+/// Print `output.count` passwords from `generator` and nothing else, for
+/// `--null` and a lone password in text; skipping analysis keeps bulk
+/// generation fast
+fn print_bare(output: &OutputArgs, mut generator: PasswordGenerator) -> Result<(), PassgenError> {
+    let sources = if output.reject_breached {
+        output.breach.sources()?
+    } else {
+        Vec::new()
+    };
+    let breached = |password: &SecretString| -> Result<bool, PassgenError> {
+        for source in &sources {
+            if source.count(password)? > 0 {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    };
+    let null = output.format.format() == OutputFormat::Null;
+
+    let mut out = io::stdout().lock();
+    for _ in 0..output.count {
+        let mut attempts = 0;
+        let password = loop {
+            let password = generator.try_next()?;
+            if !breached(&password)? {
+                break password;
+            }
+            attempts += 1;
+            if attempts == BREACH_ATTEMPTS {
+                return Err(PassgenError::Breach(format!(
+                    "{} generated passwords in a row were breached; widen the options",
+                    BREACH_ATTEMPTS
+                )));
+            }
+        };
+        exit_on_write_error(if null {
+            out.write_all(password.as_bytes())
+                .and_then(|()| out.write_all(b"\0"))
+                .and_then(|()| out.flush())
+        } else {
+            writeln!(out, "{}", password.expose_secret())
+        });
+    }
+    Ok(())
+}
+
+/// Print results in the format chosen by `output`. Text and the streamed
+/// formats print each result as soon as it is produced; the others wait for
+/// all of them. `collision` is the chance of a repeat among generated ones.
+pub fn print_results(
+    results: impl Iterator<Item = Result<PasswordStrength, PassgenError>>,
+    output: &OutputArgs,
+    verb: &str,
+    collision: Option<f64>,
+) -> Result<(), PassgenError> {
+    let format = output.format.format();
+    let mut out = io::stdout().lock();
+    if format.is_streamed() {
+        let mut stream = format::Stream::new(&mut out, format);
+        for result in results {
+            exit_on_write_error(stream.write(&result?));
+        }
+        return Ok(());
+    }
+    if format == OutputFormat::Text {
+        return print_text(&mut out, results, output, verb, collision);
+    }
+
+    let mut analysis = PasswordAnalysis::from_passwords(results.collect::<Result<_, _>>()?);
+    analysis.collision_probability = collision;
+    exit_on_write_error(write_results(&mut out, &analysis, format));
+    Ok(())
+}
+
+/// Stop quietly once the reader of stdout goes away (`passgen -c 1000 | head`)
+fn exit_on_write_error(result: io::Result<()>) {
+    match result {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => std::process::exit(0),
+        Err(err) => {
+            eprintln!("Error: failed to write output: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Fill in `breach_count` (the highest count any source reports) when a
+/// breach source was given
+pub fn check_breaches(
+    strength: &mut PasswordStrength,
+    sources: &[Box<dyn BreachCheck>],
+) -> Result<(), PassgenError> {
+    let Some(password) = &strength.password else {
+        return Ok(());
+    };
+    for source in sources {
+        let count = source.count(password)?;
+        strength.breach_count = Some(strength.breach_count.unwrap_or(0).max(count));
+    }
+    Ok(())
+}
+
+/// Write analyzed passwords in one of the formats that needs all of them at once
+fn write_results(
+    out: &mut impl Write,
+    analysis: &PasswordAnalysis,
+    format: OutputFormat,
+) -> io::Result<()> {
+    // Text rendered before writing holds every password, so it is wiped after
+    let text = match format {
+        OutputFormat::Yaml => serde_yaml::to_string(analysis).map_err(io::Error::other)?,
+        OutputFormat::Toml => toml::to_string(analysis).map_err(io::Error::other)?,
+        OutputFormat::Table => return format::write_table(out, &analysis.passwords),
+        _ => {
+            serde_json::to_writer_pretty(&mut *out, analysis)?;
+            return writeln!(out);
+        }
+    };
+    out.write_all(Zeroizing::new(text).as_bytes())
+}
+
+/// Print a lone password on its own (with the full report under
+/// `--detailed`), or number each one and finish with running averages; `verb`
+/// starts the summary line ("Generated 3 passwords")
+fn print_text(
+    out: &mut impl Write,
+    results: impl Iterator<Item = Result<PasswordStrength, PassgenError>>,
+    output: &OutputArgs,
+    verb: &str,
+    collision: Option<f64>,
+) -> Result<(), PassgenError> {
+    let strength = output.strength || output.detailed;
+    let mut results = results.peekable();
+    let first = results.next().transpose()?;
+
+    // Handle single password
+    if let Some(pwd_strength) = &first
+        && results.peek().is_none()
+    {
+        exit_on_write_error(if output.detailed {
+            write_detailed_strength(out, pwd_strength)
+        } else if strength {
+            writeln!(out, "{}", shown(pwd_strength))
+                .and_then(|()| write_strength_info(out, pwd_strength, false))
+        } else {
+            writeln!(out, "{}", shown(pwd_strength))
+        });
+        return Ok(());
+    }
+
+    let mut summary = AnalysisSummary::default();
+    let mut breached = None;
+    for result in first.map(Ok).into_iter().chain(results) {
+        let pwd_strength = result?;
+        summary.add(&pwd_strength);
+        if let Some(count) = pwd_strength.breach_count {
+            *breached.get_or_insert(0) += usize::from(count > 0);
+        }
+        exit_on_write_error((|| {
+            writeln!(out, "Password {}: {}", summary.count, shown(&pwd_strength))?;
+            if strength {
+                write_strength_info(out, &pwd_strength, output.detailed)?;
+                writeln!(out)?;
+            }
+            Ok(())
+        })());
+    }
+
+    exit_on_write_error((|| {
+        writeln!(out, "{} {} passwords", verb, summary.count)?;
+        writeln!(out, "Average entropy: {:.1} bits", summary.average_entropy)?;
+        writeln!(
+            out,
+            "Average strength score: {:.1}/4",
+            summary.average_strength_score
+        )?;
+        if let Some(breached) = breached {
+            writeln!(out, "Breached: {} of {} passwords", breached, summary.count)?;
+        }
+        if let Some(collision) = collision {
+            writeln!(
+                out,
+                "Collision probability: {}{}",
+                format_probability(collision),
+                if output.unique {
+                    " (repeats regenerated)"
+                } else {
+                    ""
+                }
+            )?;
+        }
+        Ok(())
+    })());
+    Ok(())
+}
+
+/// The password for text output, or a placeholder once omitted
+pub(crate) fn shown(strength: &PasswordStrength) -> &str {
+    match &strength.password {
+        Some(password) => password.expose_secret(),
+        None => "(omitted)",
+    }
+}
+
+/// Percentages down to 0.1%, scientific notation below that
+fn format_probability(p: f64) -> String {
+    if p == 0.0 {
+        "0".to_string()
+    } else if p < 0.001 {
+        format!("{:.1e}", p)
+    } else {
+        format!("{:.1}%", p * 100.0)
+    }
+}
+
+fn write_strength_info(
+    out: &mut impl Write,
+    pwd_strength: &PasswordStrength,
+    detailed: bool,
+) -> io::Result<()> {
+    writeln!(out, "  Entropy: {:.1} bits", pwd_strength.entropy_bits)?;
+    writeln!(
+        out,
+        "  Strength: {} ({})",
+        pwd_strength.strength_label, pwd_strength.strength_score
+    )?;
+    writeln!(out, "  Crack time: {}", pwd_strength.crack_time_display)?;
+    if let Some(count) = pwd_strength.breach_count {
+        writeln!(out, "  Breaches: {}", breach_summary(count))?;
+    }
+    if let Some(word) = &pwd_strength.banned_word {
+        writeln!(out, "  ⚠️  Contains banned word: {}", word)?;
+    }
+
+    if detailed {
+        writeln!(
+            out,
+            "  Character sets: {}",
+            pwd_strength.character_sets.join(", ")
+        )?;
+        if let Some(warning) = &pwd_strength.warning {
+            writeln!(out, "  Warning: {}", warning)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_detailed_strength(
+    out: &mut impl Write,
+    pwd_strength: &PasswordStrength,
+) -> io::Result<()> {
+    writeln!(out, "Password: {}", shown(pwd_strength))?;
+    if let Some(password) = &pwd_strength.password {
+        writeln!(out, "Length: {} characters", password.len())?;
+    }
+    writeln!(out, "Entropy: {:.1} bits", pwd_strength.entropy_bits)?;
+    if let Some(bits) = pwd_strength.generator_entropy_bits {
+        writeln!(out, "Generator entropy: {:.1} bits", bits)?;
+    }
+    writeln!(
+        out,
+        "Strength: {} ({}/4)",
+        pwd_strength.strength_label, pwd_strength.strength_score
+    )?;
+    writeln!(
+        out,
+        "Estimated crack time: {}",
+        pwd_strength.crack_time_display
+    )?;
+    if pwd_strength.crack_times.len() > 1 {
+        writeln!(out, "Crack time by attacker:")?;
+        for crack_time in &pwd_strength.crack_times {
+            writeln!(
+                out,
+                "  {:<20} {:>8.0e} guesses/s  {}",
+                crack_time.attacker, crack_time.guesses_per_second, crack_time.display
+            )?;
+        }
+    }
+    writeln!(
+        out,
+        "Character sets used: {}",
+        pwd_strength.character_sets.join(", ")
+    )?;
+    writeln!(
+        out,
+        "Estimated guesses: 10^{:.1}",
+        pwd_strength.guesses_log10
+    )?;
+    if let Some(count) = pwd_strength.breach_count {
+        writeln!(out, "Breaches: {}", breach_summary(count))?;
+    }
+
+    if !pwd_strength.matches.is_empty() {
+        writeln!(out, "Match sequence:")?;
+        for m in &pwd_strength.matches {
+            if m.token.is_empty() {
+                write!(out, "  {:<10} (omitted)", m.pattern.to_string())?;
+            } else {
+                write!(
+                    out,
+                    "  {:<10} {:?}",
+                    m.pattern.to_string(),
+                    m.token.expose_secret()
+                )?;
+            }
+            if let Some(detail) = &m.detail {
+                write!(out, " - {}", detail)?;
+            }
+            writeln!(out, " (10^{:.1} guesses)", m.guesses_log10)?;
+        }
+    }
+    if let Some(warning) = &pwd_strength.warning {
+        writeln!(out, "Weakness: {}", warning)?;
+    }
+    for suggestion in &pwd_strength.suggestions {
+        writeln!(out, "Suggestion: {}", suggestion)?;
+    }
+
+    // Add some guidance based on strength
+    match pwd_strength.strength_score {
+        0..=1 => writeln!(
+            out,
+            "⚠️  Warning: This password is weak and should not be used for sensitive accounts"
+        )?,
+        2 => writeln!(out, "ℹ️  This password has moderate strength")?,
+        3 => writeln!(out, "✅ This password has good strength")?,
+        4 => writeln!(out, "🔒 This password has excellent strength")?,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn breach_summary(count: u64) -> String {
+    match count {
+        0 => "not found in known breaches".to_string(),
+        1 => "⚠️  seen once in known breaches".to_string(),
+        n => format!("⚠️  seen {} times in known breaches", n),
+    }
+}
@@ -0,0 +1,91 @@
+use clap::{ArgMatches, Args};
+use passgen::{
+    Capitalization, PassgenError, PassphraseOptions, PasswordGenerator, PasswordOptions, Profile,
+    SmartPasswordMode, Wordlist,
+};
+use std::path::PathBuf;
+
+use super::{ConfigArgs, OutputArgs, PassphraseArgs, config};
+
+#[derive(Args, Debug, Clone)]
+pub struct PhraseArgs {
+    #[command(flatten)]
+    pub phrase: PassphraseArgs,
+
+    /// Append a digit to a random word
+    #[arg(short = 'n', long = "digit")]
+    pub digit: bool,
+
+    /// Append a symbol to a random word
+    #[arg(short = 's', long = "symbol")]
+    pub symbol: bool,
+
+    /// File of terms (one per line) the passphrase must not contain, even with l33t substitutions
+    #[arg(long, value_name = "PATH")]
+    pub banned_words: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    /// Seed the random generator for reproducible output (testing only: seeded passwords are predictable)
+    #[arg(long, value_name = "N")]
+    pub seed: Option<u64>,
+
+    #[command(flatten)]
+    pub config: ConfigArgs,
+
+    /// Words about the account owner, from the config file's `user_inputs`
+    #[arg(skip)]
+    pub user_inputs: Vec<String>,
+}
+
+impl PhraseArgs {
+    pub fn apply_profile(&mut self, profile: &Profile, matches: &ArgMatches) {
+        self.phrase.apply_profile(profile, matches);
+        config::fill(matches, "digit", &mut self.digit, &profile.numbers);
+        config::fill(matches, "symbol", &mut self.symbol, &profile.special);
+        config::fill_some(
+            matches,
+            "banned_words",
+            &mut self.banned_words,
+            &profile.banned_words,
+        );
+        if let Some(user_inputs) = &profile.user_inputs {
+            self.user_inputs = user_inputs.clone();
+        }
+        self.output.apply_profile(profile, matches);
+    }
+}
+
+pub fn run(args: PhraseArgs) -> Result<(), PassgenError> {
+    let defaults = PassphraseOptions::default();
+    let wordlist = match &args.phrase.wordlist {
+        Some(path) => match Wordlist::from_file(path) {
+            Ok(wordlist) => wordlist,
+            Err(err) => {
+                eprintln!("Error: failed to load wordlist {}: {}", path.display(), err);
+                std::process::exit(1);
+            }
+        },
+        None => defaults.wordlist,
+    };
+    let phrase_opts = PassphraseOptions {
+        words: args.phrase.words.unwrap_or(defaults.words),
+        separator: args.phrase.separator.unwrap_or(defaults.separator),
+        capitalization: args.phrase.capitalize.unwrap_or(Capitalization::Lower),
+        digit: args.digit,
+        symbol: args.symbol,
+        wordlist,
+    };
+
+    let generator = PasswordGenerator::smart_with_rng(
+        SmartPasswordMode::Passphrase(phrase_opts),
+        PasswordOptions {
+            user_inputs: args.user_inputs,
+            banned_words: args.banned_words.as_ref().map(super::load_banned_words),
+            ..Default::default()
+        },
+        super::rng(args.seed),
+    )?;
+    super::generate_and_print(&args.output, generator)
+}
@@ -0,0 +1,324 @@
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{env, fs, io};
+
+use crate::{AttackerModel, Capitalization, PassgenError};
+
+/// Defaults and named profiles read from `config.toml`.
+///
+/// Top-level keys apply to every run; a `[profile.NAME]` table selected with
+/// `--profile NAME` overrides them key by key:
+///
+/// ```toml
+/// uppercase = true
+/// lowercase = true
+/// numbers = true
+/// length = 24
+///
+/// [profile.wifi]
+/// mode = "passphrase"
+/// words = 5
+///
+/// [profile.aws]
+/// special = true
+/// symbols = "!@#$%^&*()_+-=[]{}|'"
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Config {
+    defaults: toml::Table,
+    profiles: BTreeMap<String, toml::Table>,
+    /// Relative paths in the file are resolved against this directory
+    base_dir: Option<PathBuf>,
+}
+
+/// The settings of one profile, merged over the top-level defaults.
+///
+/// Every field is optional; unset fields leave the command-line default alone.
+/// Option fields carry the names of their [`crate::PasswordOptions`]
+/// counterparts.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    pub length: Option<usize>,
+    pub uppercase: Option<bool>,
+    pub lowercase: Option<bool>,
+    pub numbers: Option<bool>,
+    pub special: Option<bool>,
+    pub avoid_ambiguous: Option<bool>,
+    pub symbols: Option<String>,
+    pub custom_sets: Option<Vec<String>>,
+    pub exclude_chars: Option<String>,
+    pub min_uppercase: Option<usize>,
+    pub min_lowercase: Option<usize>,
+    pub min_numbers: Option<usize>,
+    pub min_special: Option<usize>,
+    pub max_uppercase: Option<usize>,
+    pub max_lowercase: Option<usize>,
+    pub max_numbers: Option<usize>,
+    pub max_special: Option<usize>,
+    /// Policy file, relative to the config file
+    pub policy: Option<PathBuf>,
+    pub user_inputs: Option<Vec<String>>,
+    /// Banned-word file, relative to the config file
+    pub banned_words: Option<PathBuf>,
+
+    pub mode: Option<Mode>,
+    /// Template for `mode = "pattern"`; setting it alone selects that mode
+    pub pattern: Option<String>,
+    pub words: Option<usize>,
+    pub separator: Option<String>,
+    #[serde(deserialize_with = "parse_option")]
+    pub capitalize: Option<Capitalization>,
+    /// Passphrase wordlist, relative to the config file
+    pub wordlist: Option<PathBuf>,
+
+    pub count: Option<usize>,
+    pub unique: Option<bool>,
+    pub strength: Option<bool>,
+    pub detailed: Option<bool>,
+    pub format: Option<OutputFormat>,
+    #[serde(deserialize_with = "parse_list")]
+    pub attackers: Option<Vec<AttackerModel>>,
+}
+
+/// Which generator a profile uses
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    Random,
+    Phonetic,
+    Pattern,
+    Passphrase,
+}
+
+/// How results are printed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Human-readable report with a summary
+    #[default]
+    Text,
+    /// One aligned row per password
+    Table,
+    /// The whole analysis as pretty-printed JSON
+    Json,
+    /// One compact JSON object per password, printed as each is produced
+    Ndjson,
+    /// A header row and one row per password
+    Csv,
+    Yaml,
+    Toml,
+    /// Bare passwords terminated by NUL bytes, for `xargs -0`
+    Null,
+}
+
+impl OutputFormat {
+    pub const ALL: [OutputFormat; 8] = [
+        OutputFormat::Text,
+        OutputFormat::Table,
+        OutputFormat::Json,
+        OutputFormat::Ndjson,
+        OutputFormat::Csv,
+        OutputFormat::Yaml,
+        OutputFormat::Toml,
+        OutputFormat::Null,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Table => "table",
+            OutputFormat::Json => "json",
+            OutputFormat::Ndjson => "ndjson",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Yaml => "yaml",
+            OutputFormat::Toml => "toml",
+            OutputFormat::Null => "null",
+        }
+    }
+
+    /// Whether each password can be printed as soon as it is produced, with
+    /// no summary over all of them
+    pub fn is_streamed(self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Ndjson | OutputFormat::Csv | OutputFormat::Null
+        )
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_ascii_lowercase();
+        OutputFormat::ALL
+            .into_iter()
+            .find(|format| format.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = OutputFormat::ALL.iter().map(|f| f.name()).collect();
+                format!("unknown format '{}' (expected {})", s, names.join(", "))
+            })
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Config {
+    /// Find and load the user's config file.
+    ///
+    /// `PASSGEN_CONFIG` names the file explicitly and must exist; otherwise
+    /// `$XDG_CONFIG_HOME/passgen/config.toml` (falling back to
+    /// `~/.config/passgen/config.toml`) is read if present.
+    pub fn discover() -> Result<Option<Self>, PassgenError> {
+        if let Some(path) = env::var_os("PASSGEN_CONFIG").filter(|path| !path.is_empty()) {
+            return Self::from_file(path).map(Some);
+        }
+        let Some(path) = Self::default_path() else {
+            return Ok(None);
+        };
+        match Self::from_file(&path) {
+            Err(PassgenError::Config(_)) if !path.exists() => Ok(None),
+            loaded => loaded.map(Some),
+        }
+    }
+
+    /// The XDG location of the config file, whether or not it exists
+    pub fn default_path() -> Option<PathBuf> {
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(config_home.join("passgen").join("config.toml"))
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, PassgenError> {
+        let path = path.as_ref();
+        let err = |reason: String| PassgenError::Config(format!("{}: {}", path.display(), reason));
+        let text = fs::read_to_string(path).map_err(|e: io::Error| err(e.to_string()))?;
+        let mut config = Self::from_toml_str(&text).map_err(|e| match e {
+            PassgenError::Config(reason) => err(reason),
+            other => other,
+        })?;
+        config.base_dir = path.parent().map(Path::to_path_buf);
+        Ok(config)
+    }
+
+    pub fn from_toml_str(text: &str) -> Result<Self, PassgenError> {
+        let mut defaults: toml::Table = toml::from_str(text).map_err(config_error)?;
+        let profiles = match defaults.remove("profile") {
+            Some(profiles) => profiles.try_into().map_err(config_error)?,
+            None => BTreeMap::new(),
+        };
+        let config = Config {
+            defaults,
+            profiles,
+            base_dir: None,
+        };
+
+        // Report mistakes when the file is loaded, not when a profile that
+        // happens to contain them is first used
+        config.profile(None)?;
+        for name in config.profiles.keys() {
+            config.profile(Some(name))?;
+        }
+        Ok(config)
+    }
+
+    /// Names of the `[profile.NAME]` tables, in order
+    pub fn profile_names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+
+    /// The top-level defaults, with profile `name` merged over them if given
+    pub fn profile(&self, name: Option<&str>) -> Result<Profile, PassgenError> {
+        let mut table = self.defaults.clone();
+        if let Some(name) = name {
+            let Some(overrides) = self.profiles.get(name) else {
+                let known: Vec<_> = self.profile_names().collect();
+                return Err(PassgenError::Config(format!(
+                    "unknown profile '{}' (available: {})",
+                    name,
+                    if known.is_empty() {
+                        "none".to_string()
+                    } else {
+                        known.join(", ")
+                    }
+                )));
+            };
+            table.extend(overrides.clone());
+        }
+
+        let context = |e: toml::de::Error| match name {
+            Some(name) => PassgenError::Config(format!("profile '{}': {}", name, e.message())),
+            None => config_error(e),
+        };
+        let mut profile: Profile = toml::Value::Table(table).try_into().map_err(context)?;
+        if profile.mode == Some(Mode::Pattern) && profile.pattern.is_none() {
+            return Err(PassgenError::Config(format!(
+                "{}mode = \"pattern\" needs a pattern",
+                name.map(|name| format!("profile '{}': ", name))
+                    .unwrap_or_default()
+            )));
+        }
+        if let Some(dir) = &self.base_dir {
+            for path in [
+                &mut profile.policy,
+                &mut profile.banned_words,
+                &mut profile.wordlist,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                *path = dir.join(&*path);
+            }
+        }
+        Ok(profile)
+    }
+}
+
+impl Profile {
+    /// The generator to use: `mode`, or pattern mode when only `pattern` is set
+    pub fn mode(&self) -> Option<Mode> {
+        self.mode
+            .or_else(|| self.pattern.as_ref().map(|_| Mode::Pattern))
+    }
+}
+
+fn config_error(err: toml::de::Error) -> PassgenError {
+    PassgenError::Config(err.message().to_string())
+}
+
+/// Deserialize a string through the type's `FromStr`, as the command line does
+fn parse_option<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr<Err = String>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| value.parse().map_err(de::Error::custom))
+        .transpose()
+}
+
+fn parse_list<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr<Err = String>,
+{
+    Option::<Vec<String>>::deserialize(deserializer)?
+        .map(|values| {
+            values
+                .iter()
+                .map(|value| value.parse().map_err(de::Error::custom))
+                .collect()
+        })
+        .transpose()
+}
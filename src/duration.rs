@@ -0,0 +1,149 @@
+use std::fmt;
+
+const MINUTE: f64 = 60.0;
+const HOUR: f64 = 60.0 * MINUTE;
+const DAY: f64 = 24.0 * HOUR;
+const MONTH: f64 = 30.0 * DAY;
+const YEAR: f64 = 365.0 * DAY;
+
+/// Age of the universe, about 13.8 billion years
+const UNIVERSE_YEARS_LOG10: f64 = 10.14;
+/// Beyond 10^20 years the comparison stops meaning anything
+const FOREVER_YEARS_LOG10: f64 = 20.0;
+
+/// Unit of a [`HumanDuration::Units`] value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeUnit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Month,
+    Year,
+    Century,
+    Millennium,
+    MillionYears,
+    BillionYears,
+}
+
+/// A crack time rounded to the unit a person would use for it.
+///
+/// Built from a base-10 logarithm of seconds so that durations far beyond
+/// `f64::MAX` seconds still format; render it with a [`DurationLocale`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HumanDuration {
+    LessThanASecond,
+    Units {
+        count: u64,
+        unit: TimeUnit,
+    },
+    /// Longer than the universe has existed; `years_log10` is the magnitude
+    BeyondUniverse {
+        years_log10: f64,
+    },
+    /// So long that no attacker will ever finish
+    Forever,
+}
+
+impl HumanDuration {
+    /// Round `10^seconds_log10` seconds to the largest sensible unit
+    pub fn from_seconds_log10(seconds_log10: f64) -> Self {
+        if seconds_log10.is_nan() || seconds_log10 < 0.0 {
+            return HumanDuration::LessThanASecond;
+        }
+        let years_log10 = seconds_log10 - YEAR.log10();
+        if years_log10 >= FOREVER_YEARS_LOG10 {
+            return HumanDuration::Forever;
+        }
+        if years_log10 >= UNIVERSE_YEARS_LOG10 {
+            return HumanDuration::BeyondUniverse { years_log10 };
+        }
+
+        let seconds = 10f64.powf(seconds_log10);
+        let years = seconds / YEAR;
+        let (value, unit) = if seconds < MINUTE {
+            (seconds, TimeUnit::Second)
+        } else if seconds < HOUR {
+            (seconds / MINUTE, TimeUnit::Minute)
+        } else if seconds < DAY {
+            (seconds / HOUR, TimeUnit::Hour)
+        } else if seconds < MONTH {
+            (seconds / DAY, TimeUnit::Day)
+        } else if seconds < YEAR {
+            (seconds / MONTH, TimeUnit::Month)
+        } else if years < 100.0 {
+            (years, TimeUnit::Year)
+        } else if years < 1e3 {
+            (years / 1e2, TimeUnit::Century)
+        } else if years < 1e6 {
+            (years / 1e3, TimeUnit::Millennium)
+        } else if years < 1e9 {
+            (years / 1e6, TimeUnit::MillionYears)
+        } else {
+            (years / 1e9, TimeUnit::BillionYears)
+        };
+        // Rounding down never promotes a value into the next unit (59.6
+        // seconds reads "59 seconds", not "60 seconds"); the tolerance undoes
+        // the error of the log10 round trip
+        HumanDuration::Units {
+            count: ((value + 1e-9).floor() as u64).max(1),
+            unit,
+        }
+    }
+}
+
+/// Hook for rendering crack times in another language.
+///
+/// Implement it and pass it to [`format_duration_with`]; [`English`] is the
+/// locale used for `crack_time_display`.
+pub trait DurationLocale {
+    fn render(&self, duration: &HumanDuration) -> String;
+}
+
+/// The default English wording
+#[derive(Clone, Copy, Debug, Default)]
+pub struct English;
+
+impl DurationLocale for English {
+    fn render(&self, duration: &HumanDuration) -> String {
+        match *duration {
+            HumanDuration::LessThanASecond => "less than a second".to_string(),
+            HumanDuration::Units { count, unit } => {
+                let (one, many) = match unit {
+                    TimeUnit::Second => ("second", "seconds"),
+                    TimeUnit::Minute => ("minute", "minutes"),
+                    TimeUnit::Hour => ("hour", "hours"),
+                    TimeUnit::Day => ("day", "days"),
+                    TimeUnit::Month => ("month", "months"),
+                    TimeUnit::Year => ("year", "years"),
+                    TimeUnit::Century => ("century", "centuries"),
+                    TimeUnit::Millennium => ("millennium", "millennia"),
+                    TimeUnit::MillionYears => ("million years", "million years"),
+                    TimeUnit::BillionYears => ("billion years", "billion years"),
+                };
+                format!("{} {}", count, if count == 1 { one } else { many })
+            }
+            HumanDuration::BeyondUniverse { years_log10 } => format!(
+                "longer than the age of the universe (10^{:.0} years)",
+                years_log10.floor()
+            ),
+            HumanDuration::Forever => "effectively forever".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for HumanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&English.render(self))
+    }
+}
+
+/// Format `10^seconds_log10` seconds in English
+pub fn format_duration(seconds_log10: f64) -> String {
+    format_duration_with(seconds_log10, &English)
+}
+
+/// Format `10^seconds_log10` seconds with `locale`
+pub fn format_duration_with(seconds_log10: f64, locale: &dyn DurationLocale) -> String {
+    locale.render(&HumanDuration::from_seconds_log10(seconds_log10))
+}
@@ -0,0 +1,92 @@
+use std::fmt;
+
+/// Errors returned by the fallible (`try_*`) generation and analysis functions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PassgenError {
+    /// No character sets were selected, or all of them were emptied by filtering
+    NoCharacterSets,
+    /// The requested length cannot fit one character from every selected set
+    LengthTooShort { length: usize, sets: usize },
+    /// The per-set minimum counts add up to more than the requested length
+    MinimumsExceedLength { minimum: usize, length: usize },
+    /// The per-set maximum counts add up to less than the requested length
+    MaximumsBelowLength { maximum: usize, length: usize },
+    /// A set's minimum count is greater than its maximum count
+    MinimumExceedsMaximum { set: String },
+    /// A policy file could not be read or parsed
+    Policy(String),
+    /// No password satisfying the policy could be generated
+    PolicyUnsatisfiable(String),
+    /// The pattern contains no random elements
+    EmptyPattern,
+    /// The pattern could not be parsed; `position` is the 0-based character offset
+    InvalidPattern { position: usize, message: String },
+    /// An empty password cannot be analyzed
+    EmptyPassword,
+    /// The strength estimator rejected the password
+    Analysis(String),
+    /// A breached-password corpus could not be opened or read
+    Breach(String),
+    /// A config file could not be read or parsed, or names no such profile
+    Config(String),
+    /// The options allow fewer distinct passwords than were asked for
+    Unique(String),
+    /// A phonetic password of length 0 was requested
+    ZeroLength,
+    /// A passphrase of no words was requested
+    NoWords,
+    /// Every candidate password contained a banned word
+    BannedWordsUnavoidable { attempts: usize },
+}
+
+impl fmt::Display for PassgenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PassgenError::NoCharacterSets => {
+                write!(f, "no character sets selected or available after filtering")
+            }
+            PassgenError::LengthTooShort { length, sets } => write!(
+                f,
+                "password length {} is shorter than the {} selected character types",
+                length, sets
+            ),
+            PassgenError::MinimumsExceedLength { minimum, length } => write!(
+                f,
+                "minimum character counts require {} characters but the length is {}",
+                minimum, length
+            ),
+            PassgenError::MaximumsBelowLength { maximum, length } => write!(
+                f,
+                "maximum character counts allow only {} characters but the length is {}",
+                maximum, length
+            ),
+            PassgenError::MinimumExceedsMaximum { set } => {
+                write!(f, "minimum count for {} exceeds its maximum", set)
+            }
+            PassgenError::Policy(reason) => write!(f, "invalid policy: {}", reason),
+            PassgenError::PolicyUnsatisfiable(reason) => {
+                write!(f, "policy cannot be satisfied: {}", reason)
+            }
+            PassgenError::EmptyPattern => write!(f, "pattern contains no pattern characters"),
+            PassgenError::InvalidPattern { position, message } => {
+                write!(f, "invalid pattern at position {}: {}", position, message)
+            }
+            PassgenError::EmptyPassword => write!(f, "cannot analyze an empty password"),
+            PassgenError::Analysis(reason) => write!(f, "strength analysis failed: {}", reason),
+            PassgenError::Breach(reason) => write!(f, "breach check failed: {}", reason),
+            PassgenError::Config(reason) => write!(f, "invalid config: {}", reason),
+            PassgenError::Unique(reason) => {
+                write!(f, "not enough distinct passwords: {}", reason)
+            }
+            PassgenError::ZeroLength => write!(f, "password length must be at least 1"),
+            PassgenError::NoWords => write!(f, "a passphrase needs at least one word"),
+            PassgenError::BannedWordsUnavoidable { attempts } => write!(
+                f,
+                "every one of {} candidates contained a banned word",
+                attempts
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PassgenError {}
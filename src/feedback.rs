@@ -0,0 +1,214 @@
+use crate::SecretString;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use zxcvbn::Entropy;
+use zxcvbn::matching::Match;
+use zxcvbn::matching::patterns::MatchPattern;
+
+/// Kind of weakness zxcvbn recognized in part of a password
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchKind {
+    /// A word from a password, name or English dictionary, or a user input
+    Dictionary,
+    /// Keys that sit next to each other on a keyboard, e.g. "qwerty"
+    Spatial,
+    /// A repeated character or block, e.g. "aaa" or "abcabc"
+    Repeat,
+    /// A run of consecutive characters, e.g. "abcd" or "7531"
+    Sequence,
+    /// A recognized shape such as a recent year
+    Regex,
+    /// A calendar date, e.g. "13.05.1991"
+    Date,
+    /// Characters no other pattern explains
+    Bruteforce,
+}
+
+impl fmt::Display for MatchKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MatchKind::Dictionary => "dictionary",
+            MatchKind::Spatial => "spatial",
+            MatchKind::Repeat => "repeat",
+            MatchKind::Sequence => "sequence",
+            MatchKind::Regex => "regex",
+            MatchKind::Date => "date",
+            MatchKind::Bruteforce => "bruteforce",
+        };
+        f.write_str(name)
+    }
+}
+
+/// One segment of the cheapest decomposition zxcvbn found for a password
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatternMatch {
+    pub pattern: MatchKind,
+    /// The matched part of the password; empty once the password is omitted
+    #[serde(default, skip_serializing_if = "SecretString::is_empty")]
+    pub token: SecretString,
+    /// Character offset of the first matched character
+    pub start: usize,
+    /// Character offset of the last matched character (inclusive)
+    pub end: usize,
+    /// Estimated guesses for this segment alone, as a power of ten
+    pub guesses_log10: f64,
+    /// Human readable description, e.g. `english word "monkey" (rank 112)`
+    pub detail: Option<String>,
+}
+
+impl PatternMatch {
+    fn from_match(m: &Match) -> Self {
+        let (pattern, detail) = match &m.pattern {
+            MatchPattern::Dictionary(d) => {
+                let mut detail = format!(
+                    "{} word \"{}\" (rank {})",
+                    dictionary_name(&format!("{:?}", d.dictionary_name)),
+                    d.matched_word,
+                    d.rank
+                );
+                if d.reversed {
+                    detail.push_str(", reversed");
+                }
+                if d.l33t {
+                    detail.push_str(", l33t");
+                    // zxcvbn's own sub_display follows hash order, which
+                    // changes from run to run
+                    if let Some(sub) = &d.sub {
+                        let mut subs: Vec<String> = sub
+                            .iter()
+                            .map(|(from, to)| format!("{} -> {}", from, to))
+                            .collect();
+                        subs.sort();
+                        detail.push(' ');
+                        detail.push_str(&subs.join(", "));
+                    }
+                }
+                (MatchKind::Dictionary, Some(detail))
+            }
+            MatchPattern::Spatial(s) => {
+                let mut detail = format!("{} keyboard, {} turns", s.graph, s.turns);
+                if s.shifted_count > 0 {
+                    detail.push_str(&format!(", {} shifted", s.shifted_count));
+                }
+                (MatchKind::Spatial, Some(detail))
+            }
+            MatchPattern::Repeat(r) => (
+                MatchKind::Repeat,
+                Some(format!(
+                    "\"{}\" repeated {} times",
+                    r.base_token, r.repeat_count
+                )),
+            ),
+            MatchPattern::Sequence(s) => (
+                MatchKind::Sequence,
+                Some(format!(
+                    "{} {} sequence",
+                    if s.ascending {
+                        "ascending"
+                    } else {
+                        "descending"
+                    },
+                    s.sequence_name
+                )),
+            ),
+            MatchPattern::Regex(r) => (MatchKind::Regex, Some(r.regex_name.replace('_', " "))),
+            MatchPattern::Date(d) => (
+                MatchKind::Date,
+                Some(format!("{:04}-{:02}-{:02}", d.year, d.month, d.day)),
+            ),
+            MatchPattern::BruteForce => (MatchKind::Bruteforce, None),
+        };
+
+        PatternMatch {
+            pattern,
+            token: SecretString::from(m.token.as_str()),
+            start: m.i,
+            end: m.j,
+            guesses_log10: m.guesses.map_or(0.0, |guesses| (guesses as f64).log10()),
+            detail,
+        }
+    }
+
+    /// Whether `detail` spells out part of the password: the dictionary word,
+    /// repeated block or date it matched
+    pub(crate) fn detail_reveals_token(&self) -> bool {
+        matches!(
+            self.pattern,
+            MatchKind::Dictionary | MatchKind::Repeat | MatchKind::Date
+        )
+    }
+
+    /// Wipe the token and any detail derived from it
+    pub(crate) fn omit_token(&mut self) {
+        self.token = SecretString::new();
+        if self.detail_reveals_token() {
+            self.detail = None;
+        }
+    }
+}
+
+/// Redacts the token and, where it quotes the password, the detail
+impl fmt::Debug for PatternMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let detail = match &self.detail {
+            Some(_) if self.detail_reveals_token() => Some("[REDACTED]"),
+            detail => detail.as_deref(),
+        };
+        f.debug_struct("PatternMatch")
+            .field("pattern", &self.pattern)
+            .field("token", &self.token)
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .field("guesses_log10", &self.guesses_log10)
+            .field("detail", &detail)
+            .finish()
+    }
+}
+
+/// zxcvbn's explanation of a password: the warning and suggestions shown to
+/// users and the match sequence behind the score
+pub(crate) struct Explanation {
+    pub guesses_log10: f64,
+    pub warning: Option<String>,
+    pub suggestions: Vec<String>,
+    pub matches: Vec<PatternMatch>,
+}
+
+impl Explanation {
+    pub(crate) fn from_estimate(estimate: &Entropy) -> Self {
+        let feedback = estimate.feedback().as_ref();
+        Explanation {
+            guesses_log10: estimate.guesses_log10(),
+            warning: feedback
+                .and_then(|feedback| feedback.warning())
+                .map(|warning| warning.to_string()),
+            suggestions: feedback
+                .map(|feedback| {
+                    feedback
+                        .suggestions()
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            matches: estimate
+                .sequence()
+                .iter()
+                .map(PatternMatch::from_match)
+                .collect(),
+        }
+    }
+}
+
+/// "UsTvAndFilm" -> "us tv and film"
+fn dictionary_name(debug: &str) -> String {
+    let mut name = String::new();
+    for c in debug.chars() {
+        if c.is_uppercase() && !name.is_empty() {
+            name.push(' ');
+        }
+        name.extend(c.to_lowercase());
+    }
+    name
+}
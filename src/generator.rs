@@ -0,0 +1,288 @@
+use rand::rngs::StdRng;
+use rand::{CryptoRng, RngCore, SeedableRng};
+use std::collections::HashSet;
+use std::hash::{BuildHasher, RandomState};
+
+use crate::{
+    PassgenError, PasswordOptions, PasswordStrength, Pattern, SecretString, SmartPasswordMode,
+    analyze_password, analyze_smart_password, avoid_banned_words, generate_passphrase_with_rng,
+    keyspace_size, password_entropy, smart_entropy, try_generate_password_with_rng,
+    try_generate_pattern_password_with_rng, try_generate_phonetic_password_with_rng,
+    validate_options,
+};
+
+/// An endless stream of passwords for bulk generation.
+///
+/// Passwords are produced one at a time, so memory use does not grow with the
+/// number taken; use [`Iterator::take`] to bound it, and
+/// [`PasswordGenerator::analyzed`] to get strength analysis alongside.
+///
+/// ```
+/// use passgen::{PasswordGenerator, PasswordOptions};
+///
+/// let opts = PasswordOptions {
+///     length: 20,
+///     lowercase: true,
+///     numbers: true,
+///     ..Default::default()
+/// };
+/// for password in PasswordGenerator::new(opts)?.take(3) {
+///     assert_eq!(password.len(), 20);
+/// }
+/// # Ok::<(), passgen::PassgenError>(())
+/// ```
+pub struct PasswordGenerator<R = StdRng> {
+    opts: PasswordOptions,
+    mode: Option<SmartPasswordMode>,
+    rng: R,
+    distinct: Option<Distinct>,
+}
+
+impl PasswordGenerator {
+    /// Random passwords from `opts`, which are checked up front
+    pub fn new(opts: PasswordOptions) -> Result<Self, PassgenError> {
+        Self::with_rng(opts, StdRng::from_entropy())
+    }
+
+    /// Passwords from a phonetic, pattern or passphrase generator
+    pub fn smart(mode: SmartPasswordMode, opts: PasswordOptions) -> Result<Self, PassgenError> {
+        Self::smart_with_rng(mode, opts, StdRng::from_entropy())
+    }
+}
+
+impl<R: RngCore + CryptoRng> PasswordGenerator<R> {
+    /// Random passwords from `opts`, drawing randomness from `rng`
+    pub fn with_rng(opts: PasswordOptions, rng: R) -> Result<Self, PassgenError> {
+        validate_options(&opts)?;
+        Ok(PasswordGenerator {
+            opts,
+            mode: None,
+            rng,
+            distinct: None,
+        })
+    }
+
+    /// Passwords from a phonetic, pattern or passphrase generator, drawing
+    /// randomness from `rng`
+    pub fn smart_with_rng(
+        mode: SmartPasswordMode,
+        opts: PasswordOptions,
+        rng: R,
+    ) -> Result<Self, PassgenError> {
+        match &mode {
+            SmartPasswordMode::Phonetic if opts.length == 0 => {
+                return Err(PassgenError::ZeroLength);
+            }
+            SmartPasswordMode::Pattern(pattern)
+                if Pattern::parse_with_options(pattern, &opts)?.entropy() == 0.0 =>
+            {
+                return Err(PassgenError::EmptyPattern);
+            }
+            SmartPasswordMode::Passphrase(phrase_opts) if phrase_opts.words == 0 => {
+                return Err(PassgenError::NoWords);
+            }
+            _ => {}
+        }
+        Ok(PasswordGenerator {
+            opts,
+            mode: Some(mode),
+            rng,
+            distinct: None,
+        })
+    }
+
+    /// Never yield the same password twice, failing up front when the options
+    /// allow fewer than `count` distinct passwords.
+    ///
+    /// Phonetic entropy is an average over the substitutions rather than a
+    /// count of passwords, so a phonetic generator is only checked as it runs
+    /// and fails once new passwords stop turning up.
+    ///
+    /// Only a keyed 64-bit hash of each password is remembered, not the
+    /// password itself.
+    pub fn unique(mut self, count: usize) -> Result<Self, PassgenError> {
+        let keyspace = self.keyspace_bits();
+        let space = keyspace_size(keyspace);
+        let counted = !matches!(self.mode, Some(SmartPasswordMode::Phonetic));
+        if counted && count as f64 > space {
+            return Err(PassgenError::Unique(format!(
+                "{} requested but these options allow only {}; lengthen the password or add characters",
+                count, space
+            )));
+        }
+        self.distinct = Some(Distinct::new(keyspace));
+        Ok(self)
+    }
+
+    /// Entropy in bits of each password
+    pub fn entropy_bits(&self) -> f64 {
+        match &self.mode {
+            None => password_entropy(&self.opts),
+            Some(mode) => smart_entropy(mode, &self.opts),
+        }
+    }
+
+    /// log2 of the number of distinct passwords, outside phonetic mode.
+    /// Only patterns differ from their entropy, which weights variable
+    /// lengths by how likely they are; see [`Pattern::keyspace_bits`].
+    fn keyspace_bits(&self) -> f64 {
+        match &self.mode {
+            Some(SmartPasswordMode::Pattern(pattern)) => {
+                Pattern::parse_with_options(pattern, &self.opts)
+                    .map_or(0.0, |pattern| pattern.keyspace_bits())
+            }
+            _ => self.entropy_bits(),
+        }
+    }
+
+    /// The next password, or the error that prevented it (e.g. every
+    /// candidate containing a banned word)
+    pub fn try_next(&mut self) -> Result<SecretString, PassgenError> {
+        let Some(distinct) = &mut self.distinct else {
+            return generate(&self.opts, self.mode.as_ref(), &mut self.rng);
+        };
+        for _ in 0..distinct.attempts {
+            let password = generate(&self.opts, self.mode.as_ref(), &mut self.rng)?;
+            if distinct.insert(&password) {
+                return Ok(password);
+            }
+        }
+        Err(distinct.exhausted())
+    }
+
+    /// The next password with its strength analysis, given the
+    /// [`PasswordGenerator::entropy_bits`] all passwords share
+    fn try_next_analyzed(&mut self, entropy: f64) -> Result<PasswordStrength, PassgenError> {
+        let password = self.try_next()?;
+        match &self.mode {
+            None => analyze_password(&password, &self.opts, Some(entropy)),
+            Some(mode) => analyze_smart_password(&password, mode, &self.opts, entropy),
+        }
+    }
+
+    /// Analyze each password as it is generated
+    pub fn analyzed(self) -> AnalyzedPasswords<R> {
+        AnalyzedPasswords {
+            entropy: self.entropy_bits(),
+            generator: self,
+        }
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<R: RngCore + CryptoRng + Send + 'static> PasswordGenerator<R> {
+    /// Generate and analyze passwords on a thread pool, as an endless stream
+    /// like [`PasswordGenerator::analyzed`].
+    ///
+    /// Each password draws from its own generator seeded from this one's
+    /// `rng`. With `parallelism.ordered`, a seeded generator therefore gives
+    /// the same sequence whatever the number of threads, though not the one
+    /// [`PasswordGenerator::analyzed`] gives.
+    pub fn parallel(self, parallelism: crate::Parallelism) -> crate::ParallelResults {
+        let entropy = self.entropy_bits();
+        let PasswordGenerator {
+            opts,
+            mode,
+            mut rng,
+            distinct,
+        } = self;
+        let seeds = std::iter::repeat_with(move || {
+            let mut seed = <StdRng as SeedableRng>::Seed::default();
+            rng.fill_bytes(&mut seed);
+            seed
+        });
+        let mut results = crate::parallel::run(seeds, parallelism, move |seed| {
+            PasswordGenerator {
+                opts: opts.clone(),
+                mode: mode.clone(),
+                rng: StdRng::from_seed(seed),
+                distinct: None,
+            }
+            .try_next_analyzed(entropy)
+        });
+        // Repeats are only spotted once they come back from the workers
+        results.distinct = distinct;
+        results
+    }
+}
+
+impl<R: RngCore + CryptoRng> Iterator for PasswordGenerator<R> {
+    type Item = SecretString;
+
+    /// # Panics
+    ///
+    /// Panics if no password can be generated; see
+    /// [`PasswordGenerator::try_next`].
+    fn next(&mut self) -> Option<SecretString> {
+        Some(self.try_next().unwrap_or_else(|err| panic!("{}", err)))
+    }
+}
+
+/// An endless stream of analyzed passwords, from [`PasswordGenerator::analyzed`]
+pub struct AnalyzedPasswords<R = StdRng> {
+    generator: PasswordGenerator<R>,
+    entropy: f64,
+}
+
+impl<R: RngCore + CryptoRng> Iterator for AnalyzedPasswords<R> {
+    type Item = Result<PasswordStrength, PassgenError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.generator.try_next_analyzed(self.entropy))
+    }
+}
+
+fn generate<R: RngCore + CryptoRng>(
+    opts: &PasswordOptions,
+    mode: Option<&SmartPasswordMode>,
+    rng: &mut R,
+) -> Result<SecretString, PassgenError> {
+    match mode {
+        None => try_generate_password_with_rng(opts, rng),
+        Some(SmartPasswordMode::Phonetic) => try_generate_phonetic_password_with_rng(opts, rng),
+        Some(SmartPasswordMode::Pattern(pattern)) => {
+            try_generate_pattern_password_with_rng(pattern, opts, rng)
+        }
+        Some(SmartPasswordMode::Passphrase(phrase_opts)) => avoid_banned_words(opts, rng, |rng| {
+            Ok(generate_passphrase_with_rng(phrase_opts, rng))
+        }),
+    }
+}
+
+/// The passwords a [unique](PasswordGenerator::unique) generator has yielded,
+/// as keyed hashes. A hash collision only costs a redraw.
+pub(crate) struct Distinct {
+    keys: RandomState,
+    seen: HashSet<u64>,
+    /// Repeats in a row after which the space is taken to be used up
+    pub(crate) attempts: usize,
+}
+
+impl Distinct {
+    fn new(entropy_bits: f64) -> Self {
+        // Finding the last few unused passwords of a small space takes
+        // about as many draws as the space is large
+        let attempts = (keyspace_size(entropy_bits) * 20.0).clamp(1_000.0, 1e9) as usize;
+        Distinct {
+            keys: RandomState::new(),
+            seen: HashSet::new(),
+            attempts,
+        }
+    }
+
+    /// Record `password`, returning whether it is new
+    pub(crate) fn insert(&mut self, password: &str) -> bool {
+        self.seen.insert(self.keys.hash_one(password))
+    }
+
+    /// The error once `attempts` draws in a row were all repeats, which
+    /// happens when the options allow fewer passwords than estimated (e.g.
+    /// because of banned words)
+    pub(crate) fn exhausted(&self) -> PassgenError {
+        PassgenError::Unique(format!(
+            "no new password in {} attempts after {} distinct ones",
+            self.attempts,
+            self.seen.len()
+        ))
+    }
+}
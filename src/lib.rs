@@ -1,8 +1,60 @@
+use feedback::Explanation;
+use rand::distributions::{Distribution, Uniform};
 use rand::rngs::StdRng;
-use rand::seq::SliceRandom;
-use rand::{Rng, SeedableRng};
+use rand::seq::{SliceRandom, index};
+use rand::{CryptoRng, RngCore, SeedableRng};
 use serde::{Deserialize, Serialize};
-use zxcvbn::zxcvbn;
+use zeroize::Zeroize;
+use zxcvbn::{Entropy, ZxcvbnError, zxcvbn};
+
+mod attack;
+mod banned;
+mod breach;
+mod config;
+mod duration;
+mod error;
+mod feedback;
+mod generator;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod passphrase;
+mod pattern;
+mod policy;
+mod secret;
+
+pub use attack::{AttackerModel, CrackTime};
+pub use banned::BannedWords;
+#[cfg(feature = "hibp")]
+pub use breach::RangeClient;
+pub use breach::{BreachCheck, BreachCorpus, build_index, sha1};
+pub use config::{Config, Mode, OutputFormat, Profile};
+pub use duration::{
+    DurationLocale, English, HumanDuration, TimeUnit, format_duration, format_duration_with,
+};
+pub use error::PassgenError;
+pub use feedback::{MatchKind, PatternMatch};
+pub use generator::{AnalyzedPasswords, PasswordGenerator};
+#[cfg(feature = "parallel")]
+pub use parallel::{ParallelResults, Parallelism, analyze_parallel};
+pub use passphrase::{
+    Capitalization, PassphraseOptions, Wordlist, analyze_passphrase_strength, generate_passphrase,
+    generate_passphrase_with_rng, generate_passphrase_with_strength, passphrase_entropy,
+    try_analyze_passphrase_strength,
+};
+pub use pattern::Pattern;
+pub use policy::{PasswordPolicy, PolicyViolation, check_policy};
+pub use secret::SecretString;
+
+pub const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+pub const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+pub const NUMBERS: &str = "0123456789";
+pub const SPECIAL: &str = "!@#$%^&*()-=_+[]{}|;:,.<>?";
+
+const PHONETIC_CONSONANTS: &str = "bcdfghjklmnpqrstvwxyz";
+const PHONETIC_VOWELS: &str = "aeiou";
+const PHONETIC_DIGITS: &str = "0123456789";
+const PHONETIC_SYMBOLS: &str = "!@#$%^&*";
+const AMBIGUOUS: &[char] = &['I', 'O', 'l', '0', '1'];
 
 #[derive(Clone, Debug, Default)]
 pub struct PasswordOptions {
@@ -12,17 +64,129 @@ pub struct PasswordOptions {
     pub numbers: bool,
     pub special: bool,
     pub avoid_ambiguous: bool,
+    /// Replaces the default special character set when `special` is enabled
+    pub symbols: Option<String>,
+    /// Additional character sets; each contributes at least one character
+    pub custom_sets: Vec<String>,
+    /// Characters that must never appear in the password
+    pub exclude_chars: String,
+    /// Minimum number of uppercase letters (a non-zero value enables the set)
+    pub min_uppercase: usize,
+    /// Minimum number of lowercase letters (a non-zero value enables the set)
+    pub min_lowercase: usize,
+    /// Minimum number of digits (a non-zero value enables the set)
+    pub min_numbers: usize,
+    /// Minimum number of special characters (a non-zero value enables the set)
+    pub min_special: usize,
+    pub max_uppercase: Option<usize>,
+    pub max_lowercase: Option<usize>,
+    pub max_numbers: Option<usize>,
+    pub max_special: Option<usize>,
+    /// Rules the generated password must satisfy, on top of the options above
+    pub policy: Option<PasswordPolicy>,
+    /// Words about the account owner (name, username, email, company) that
+    /// strength analysis treats as easy to guess
+    pub user_inputs: Vec<String>,
+    /// Terms generators never emit and analysis treats as trivially guessable
+    pub banned_words: Option<BannedWords>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PasswordStrength {
-    pub password: String,
+    /// `None` once [`PasswordStrength::omit_password`] has been called, and
+    /// then left out of serialized output; an empty password is still shown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<SecretString>,
     pub entropy_bits: f64,
     pub strength_score: u8, // 0-4 (0=very weak, 4=very strong)
     pub strength_label: String,
     pub crack_time_seconds: f64,
     pub crack_time_display: String,
+    /// `crack_time_seconds` as a power of ten; stays exact for crack times
+    /// too large for an `f64`
+    #[serde(default)]
+    pub crack_time_log10: f64,
     pub character_sets: Vec<String>,
+    /// Entropy of the generator that produced the password, when known.
+    ///
+    /// `entropy_bits` may be an estimate from the characters observed in the
+    /// output; this is the entropy of the process itself (e.g. 21·5 alternation
+    /// for phonetic passwords) and drives the crack time estimate.
+    pub generator_entropy_bits: Option<f64>,
+    /// zxcvbn's estimate of the guesses needed, as a power of ten
+    #[serde(default)]
+    pub guesses_log10: f64,
+    /// Why zxcvbn considers the password weak, if it does
+    #[serde(default)]
+    pub warning: Option<String>,
+    /// zxcvbn's advice for a stronger password
+    #[serde(default)]
+    pub suggestions: Vec<String>,
+    /// The dictionary words, keyboard walks, repeats, sequences and dates
+    /// zxcvbn decomposed the password into
+    #[serde(default)]
+    pub matches: Vec<PatternMatch>,
+    /// Crack time under each attacker scenario; the first one is the
+    /// headline `crack_time_seconds`
+    #[serde(default)]
+    pub crack_times: Vec<CrackTime>,
+    /// Times the password appears in a breach corpus; `None` when no corpus
+    /// was checked
+    #[serde(default)]
+    pub breach_count: Option<u64>,
+    /// The banned term the password contains, if any
+    #[serde(default)]
+    pub banned_word: Option<String>,
+}
+
+impl PasswordStrength {
+    /// Wipe the password, keeping only its analysis: the match sequence
+    /// keeps its kinds, offsets and guesses but loses the matched tokens and
+    /// any details that quote them
+    pub fn omit_password(&mut self) {
+        self.password = None;
+        self.matches.iter_mut().for_each(PatternMatch::omit_token);
+    }
+
+    /// Guesses an attacker needs, as a power of ten: the full keyspace of the
+    /// generator when known, otherwise the smaller of the character-set
+    /// estimate and zxcvbn's pattern-aware estimate
+    pub fn attack_guesses_log10(&self) -> f64 {
+        match self.generator_entropy_bits {
+            Some(bits) => bits * std::f64::consts::LOG10_2,
+            None => (self.entropy_bits * std::f64::consts::LOG10_2).min(self.guesses_log10),
+        }
+    }
+
+    /// Recompute the crack times for `attackers` (the built-in scenarios when
+    /// empty); the first attacker sets `crack_time_seconds`
+    pub fn apply_attackers(&mut self, attackers: &[AttackerModel]) {
+        let defaults;
+        let attackers = if attackers.is_empty() {
+            defaults = AttackerModel::defaults();
+            &defaults
+        } else {
+            attackers
+        };
+
+        let guesses_log10 = self.attack_guesses_log10();
+        self.crack_times = attackers
+            .iter()
+            .map(|attacker| {
+                let seconds_log10 = attacker.seconds_log10_for(guesses_log10);
+                CrackTime {
+                    attacker: attacker.name.clone(),
+                    guesses_per_second: attacker.guesses_per_second,
+                    seconds: attacker.seconds_for(guesses_log10),
+                    seconds_log10,
+                    display: format_duration(seconds_log10),
+                }
+            })
+            .collect();
+        self.crack_time_seconds = self.crack_times[0].seconds;
+        self.crack_time_log10 = self.crack_times[0].seconds_log10;
+        self.crack_time_display = self.crack_times[0].display.clone();
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,103 +195,425 @@ pub struct PasswordAnalysis {
     pub count: usize,
     pub average_entropy: f64,
     pub average_strength_score: f64,
+    /// Chance that a batch of `count` generated passwords repeats one; `None`
+    /// for passwords that were analyzed rather than generated
+    #[serde(default)]
+    pub collision_probability: Option<f64>,
+}
+
+impl PasswordAnalysis {
+    /// Summarize already analyzed passwords
+    pub fn from_passwords(passwords: Vec<PasswordStrength>) -> Self {
+        let mut summary = AnalysisSummary::default();
+        passwords.iter().for_each(|p| summary.add(p));
+        PasswordAnalysis {
+            passwords,
+            count: summary.count,
+            average_entropy: summary.average_entropy,
+            average_strength_score: summary.average_strength_score,
+            collision_probability: None,
+        }
+    }
+}
+
+/// The averages of a [`PasswordAnalysis`], kept up to date one password at a
+/// time so that streams of any length can be summarized.
+///
+/// The averages are 0 until a password is added.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AnalysisSummary {
+    pub count: usize,
+    pub average_entropy: f64,
+    pub average_strength_score: f64,
+}
+
+impl AnalysisSummary {
+    pub fn add(&mut self, strength: &PasswordStrength) {
+        self.count += 1;
+        // Running means stay accurate over millions of values, where a
+        // running total would lose precision
+        let n = self.count as f64;
+        self.average_entropy += (strength.entropy_bits - self.average_entropy) / n;
+        self.average_strength_score +=
+            (strength.strength_score as f64 - self.average_strength_score) / n;
+    }
 }
 
 /// Generate a password using the provided options.
-/// When no sets are effectively selected (after filtering), returns a default password.
-pub fn generate_password(opts: &PasswordOptions) -> String {
-    const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
-    const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
-    const NUMBERS: &str = "0123456789";
-    const SPECIAL: &str = "!@#$%^&*()-=_+[]{}|;:,.<>?";
-    const AMBIGUOUS_UPPER: &[char] = &['I', 'O'];
-    const AMBIGUOUS_LOWER: &[char] = &['l'];
-    const AMBIGUOUS_NUMBERS: &[char] = &['0', '1'];
-
-    let mut rng: StdRng = StdRng::from_entropy();
+///
+/// When the length is shorter than the number of selected sets, some sets are
+/// left out; use [`try_generate_password`] to treat that as an error instead.
+///
+/// # Panics
+///
+/// Panics when no character sets are selected or available after filtering,
+/// when the minimum or maximum counts cannot be satisfied, when a policy is
+/// set and cannot be satisfied, or when every candidate contains a banned
+/// word.
+pub fn generate_password(opts: &PasswordOptions) -> SecretString {
+    generate_password_with_rng(opts, &mut StdRng::from_entropy())
+}
 
-    // Build filtered sets based on options
-    let build_filtered = |set: &str, ambiguous: &[char]| -> Vec<char> {
-        if opts.avoid_ambiguous {
-            set.chars().filter(|c| !ambiguous.contains(c)).collect()
-        } else {
-            set.chars().collect()
-        }
-    };
+/// Generate a password drawing randomness from `rng`.
+///
+/// Behaves like [`generate_password`]; pass a seeded RNG for reproducible output.
+pub fn generate_password_with_rng<R: RngCore + CryptoRng>(
+    opts: &PasswordOptions,
+    rng: &mut R,
+) -> SecretString {
+    if opts.policy.is_some() {
+        return try_generate_password_with_rng(opts, rng).unwrap_or_else(|err| panic!("{}", err));
+    }
 
-    let mut selected_sets: Vec<Vec<char>> = Vec::with_capacity(4);
-    if opts.uppercase {
-        let v = build_filtered(UPPERCASE, AMBIGUOUS_UPPER);
-        if !v.is_empty() {
-            selected_sets.push(v);
+    let sets = build_character_sets(opts);
+    match check_constraints(&sets, opts.length) {
+        Ok(()) | Err(PassgenError::LengthTooShort { .. }) => {
+            avoid_banned_words(opts, rng, |rng| fill_password(&sets, opts.length, rng))
+                .unwrap_or_else(|err| panic!("{}", err))
         }
+        Err(err) => panic!("{}", err),
     }
-    if opts.lowercase {
-        let v = build_filtered(LOWERCASE, AMBIGUOUS_LOWER);
-        if !v.is_empty() {
-            selected_sets.push(v);
+}
+
+/// Generate a password, returning an error when the options cannot be satisfied.
+pub fn try_generate_password(opts: &PasswordOptions) -> Result<SecretString, PassgenError> {
+    try_generate_password_with_rng(opts, &mut StdRng::from_entropy())
+}
+
+/// Generate a password drawing randomness from `rng`, returning an error when
+/// the options cannot be satisfied.
+pub fn try_generate_password_with_rng<R: RngCore + CryptoRng>(
+    opts: &PasswordOptions,
+    rng: &mut R,
+) -> Result<SecretString, PassgenError> {
+    if let Some(policy) = &opts.policy {
+        return avoid_banned_words(opts, rng, |rng| generate_with_policy(opts, policy, rng));
+    }
+
+    let sets = build_character_sets(opts);
+    check_constraints(&sets, opts.length)?;
+
+    avoid_banned_words(opts, rng, |rng| fill_password(&sets, opts.length, rng))
+}
+
+/// How many candidates to draw before giving up on avoiding banned words
+const BANNED_WORD_ATTEMPTS: usize = 1000;
+
+/// Draw candidates from `generate` until one contains none of the banned words
+fn avoid_banned_words<R: RngCore + CryptoRng>(
+    opts: &PasswordOptions,
+    rng: &mut R,
+    mut generate: impl FnMut(&mut R) -> Result<SecretString, PassgenError>,
+) -> Result<SecretString, PassgenError> {
+    let Some(banned) = opts
+        .banned_words
+        .as_ref()
+        .filter(|banned| !banned.is_empty())
+    else {
+        return generate(rng);
+    };
+    for _ in 0..BANNED_WORD_ATTEMPTS {
+        let password = generate(rng)?;
+        if banned.find(&password).is_none() {
+            return Ok(password);
         }
     }
-    if opts.numbers {
-        let v = build_filtered(NUMBERS, AMBIGUOUS_NUMBERS);
-        if !v.is_empty() {
-            selected_sets.push(v);
+
+    Err(PassgenError::BannedWordsUnavoidable {
+        attempts: BANNED_WORD_ATTEMPTS,
+    })
+}
+
+/// How many candidates to draw before giving up on a policy
+const POLICY_ATTEMPTS: usize = 1000;
+
+/// Generate a password satisfying `policy`, retrying when random output breaks
+/// a rule the generator cannot enforce directly (repeated runs, substrings)
+fn generate_with_policy<R: RngCore + CryptoRng>(
+    opts: &PasswordOptions,
+    policy: &PasswordPolicy,
+    rng: &mut R,
+) -> Result<SecretString, PassgenError> {
+    let prefix = policy.required_prefix.as_deref().unwrap_or_default();
+    let suffix = policy.required_suffix.as_deref().unwrap_or_default();
+    let body_opts = apply_policy(opts, policy)?;
+    let sets = build_character_sets(&body_opts);
+    check_constraints(&sets, body_opts.length)?;
+
+    for _ in 0..POLICY_ATTEMPTS {
+        let body = fill_password(&sets, body_opts.length, rng)?;
+        let mut password = SecretString::with_capacity(prefix.len() + body.len() + suffix.len());
+        password.push_str(prefix);
+        password.push_str(&body);
+        password.push_str(suffix);
+        if check_policy(&password, policy).is_empty() {
+            return Ok(password);
         }
     }
-    if opts.special {
-        let v = build_filtered(SPECIAL, &[]);
-        if !v.is_empty() {
-            selected_sets.push(v);
+
+    Err(PassgenError::PolicyUnsatisfiable(format!(
+        "no compliant password found in {} attempts",
+        POLICY_ATTEMPTS
+    )))
+}
+
+/// Options for the randomly generated part of a policy-compliant password,
+/// i.e. everything between the required prefix and suffix
+fn apply_policy(
+    opts: &PasswordOptions,
+    policy: &PasswordPolicy,
+) -> Result<PasswordOptions, PassgenError> {
+    let fixed_len = policy
+        .required_prefix
+        .as_deref()
+        .unwrap_or_default()
+        .chars()
+        .count()
+        + policy
+            .required_suffix
+            .as_deref()
+            .unwrap_or_default()
+            .chars()
+            .count();
+
+    let mut length = opts.length.max(policy.min_length);
+    if let Some(max_length) = policy.max_length {
+        length = length.min(max_length);
+    }
+    if length < policy.min_length || fixed_len >= length {
+        return Err(PassgenError::PolicyUnsatisfiable(
+            "the length limits leave no room for generated characters".to_string(),
+        ));
+    }
+
+    let mut exclude_chars = opts.exclude_chars.clone();
+    exclude_chars.push_str(&policy.forbidden_chars);
+
+    Ok(PasswordOptions {
+        length: length - fixed_len,
+        exclude_chars,
+        min_uppercase: opts.min_uppercase.max(policy.min_uppercase),
+        min_lowercase: opts.min_lowercase.max(policy.min_lowercase),
+        min_numbers: opts.min_numbers.max(policy.min_numbers),
+        min_special: opts.min_special.max(policy.min_special),
+        policy: None,
+        ..opts.clone()
+    })
+}
+
+/// A named character set after ambiguous and excluded characters are removed,
+/// with the number of characters the password must draw from it
+struct CharacterSet {
+    name: String,
+    chars: Vec<char>,
+    min: usize,
+    max: Option<usize>,
+}
+
+/// Whether `c` survives the ambiguous-character and exclusion filters
+pub(crate) fn is_allowed(opts: &PasswordOptions, c: char) -> bool {
+    let ambiguous = opts.avoid_ambiguous && AMBIGUOUS.contains(&c);
+    !ambiguous && !opts.exclude_chars.contains(c)
+}
+
+/// Build the selected character sets, dropping any emptied by filtering
+fn build_character_sets(opts: &PasswordOptions) -> Vec<CharacterSet> {
+    // Build filtered sets based on options
+    let build_filtered = |set: &str| -> Vec<char> {
+        let mut chars: Vec<char> = Vec::with_capacity(set.len());
+        for c in set.chars() {
+            if is_allowed(opts, c) && !chars.contains(&c) {
+                chars.push(c);
+            }
         }
+        chars
+    };
+
+    // Every selected set contributes at least one character
+    let mut candidates: Vec<(String, &str, usize, Option<usize>)> =
+        Vec::with_capacity(4 + opts.custom_sets.len());
+    if opts.uppercase || opts.min_uppercase > 0 {
+        candidates.push((
+            "uppercase".to_string(),
+            UPPERCASE,
+            opts.min_uppercase.max(1),
+            opts.max_uppercase,
+        ));
+    }
+    if opts.lowercase || opts.min_lowercase > 0 {
+        candidates.push((
+            "lowercase".to_string(),
+            LOWERCASE,
+            opts.min_lowercase.max(1),
+            opts.max_lowercase,
+        ));
+    }
+    if opts.numbers || opts.min_numbers > 0 {
+        candidates.push((
+            "numbers".to_string(),
+            NUMBERS,
+            opts.min_numbers.max(1),
+            opts.max_numbers,
+        ));
+    }
+    if opts.special || opts.min_special > 0 {
+        candidates.push((
+            "special".to_string(),
+            opts.symbols.as_deref().unwrap_or(SPECIAL),
+            opts.min_special.max(1),
+            opts.max_special,
+        ));
+    }
+    for set in &opts.custom_sets {
+        candidates.push((format!("custom({})", set), set.as_str(), 1, None));
     }
 
-    // Generalized warning: length is less than the number of effective sets
-    let effective_sets_count = selected_sets.len();
-    if opts.length < effective_sets_count {
-        eprintln!(
-            "Warning: Password length is shorter than the number of selected character types; some types may be omitted."
-        );
+    candidates
+        .into_iter()
+        .filter_map(|(name, set, min, max)| {
+            let chars = build_filtered(set);
+            (!chars.is_empty()).then_some(CharacterSet {
+                name,
+                chars,
+                min,
+                max,
+            })
+        })
+        .collect()
+}
+
+/// Check that a password of `length` characters can satisfy every set's counts
+fn check_constraints(sets: &[CharacterSet], length: usize) -> Result<(), PassgenError> {
+    if sets.is_empty() {
+        return Err(PassgenError::NoCharacterSets);
+    }
+    if length < sets.len() {
+        return Err(PassgenError::LengthTooShort {
+            length,
+            sets: sets.len(),
+        });
+    }
+    if let Some(set) = sets
+        .iter()
+        .find(|set| set.max.is_some_and(|max| max < set.min))
+    {
+        return Err(PassgenError::MinimumExceedsMaximum {
+            set: set.name.clone(),
+        });
     }
 
-    // Build the full pool once
-    let mut all_chars_pool: Vec<char> = Vec::new();
-    for set in &selected_sets {
-        all_chars_pool.extend_from_slice(set);
+    let minimum: usize = sets.iter().map(|set| set.min).sum();
+    if minimum > length {
+        return Err(PassgenError::MinimumsExceedLength { minimum, length });
+    }
+    // Uncapped sets only lift the limit if they have characters outside
+    // every capped set
+    let capped: Vec<&CharacterSet> = sets.iter().filter(|set| set.max.is_some()).collect();
+    let bounded = character_pool(sets)
+        .iter()
+        .all(|c| capped.iter().any(|set| set.chars.contains(c)));
+    let maximum: usize = capped.iter().filter_map(|set| set.max).sum();
+    if bounded && maximum < length {
+        return Err(PassgenError::MaximumsBelowLength { maximum, length });
     }
 
-    if all_chars_pool.is_empty() {
-        eprintln!(
-            "Warning: No character sets selected or available after filtering. Returning a default password."
-        );
-        return String::from("DefaultPwd1!");
+    Ok(())
+}
+
+/// Union of the selected sets, each character appearing once
+fn character_pool(sets: &[CharacterSet]) -> Vec<char> {
+    let mut pool: Vec<char> = Vec::new();
+    for set in sets {
+        for &c in &set.chars {
+            if !pool.contains(&c) {
+                pool.push(c);
+            }
+        }
     }
+    pool
+}
+
+/// Fill a password of `length` characters, taking each set's minimum first.
+///
+/// Fails rather than returning a short password when the maximum counts run
+/// out before `length` is reached.
+fn fill_password<R: RngCore + CryptoRng>(
+    sets: &[CharacterSet],
+    length: usize,
+    rng: &mut R,
+) -> Result<SecretString, PassgenError> {
+    // Build the full pool once
+    let all_chars_pool = character_pool(sets);
 
-    // Ensure at least one character from each selected set
-    let mut password_chars: Vec<char> = Vec::with_capacity(opts.length.max(1));
-    for set in &selected_sets {
-        let idx = rng.gen_range(0..set.len());
-        password_chars.push(set[idx]);
+    // Ensure the minimum number of characters from each selected set
+    let mut password_chars: Vec<char> = Vec::with_capacity(length.max(1));
+    for set in sets {
+        let pick = Uniform::from(0..set.chars.len());
+        password_chars.extend(
+            pick.sample_iter(&mut *rng)
+                .take(set.min)
+                .map(|i| set.chars[i]),
+        );
     }
 
     // If we already have enough chars, shuffle, truncate and return
-    if password_chars.len() >= opts.length {
-        password_chars.shuffle(&mut rng);
-        password_chars.truncate(opts.length);
-        return password_chars.into_iter().collect();
+    if password_chars.len() >= length {
+        password_chars.shuffle(rng);
+        password_chars.truncate(length);
+        return Ok(SecretString::from_chars(password_chars));
     }
 
     // Fill the remaining length with random picks from the pool
-    let remaining_len = opts.length - password_chars.len();
-    for _ in 0..remaining_len {
-        let idx = rng.gen_range(0..all_chars_pool.len());
-        password_chars.push(all_chars_pool[idx]);
+    let remaining_len = length - password_chars.len();
+    if sets.iter().all(|set| set.max.is_none()) {
+        let pick = Uniform::from(0..all_chars_pool.len());
+        password_chars.extend(
+            pick.sample_iter(&mut *rng)
+                .take(remaining_len)
+                .map(|i| all_chars_pool[i]),
+        );
+    } else {
+        // Only draw characters whose sets are still below their maximum
+        let mut counts: Vec<usize> = sets
+            .iter()
+            .map(|set| {
+                password_chars
+                    .iter()
+                    .filter(|c| set.chars.contains(c))
+                    .count()
+            })
+            .collect();
+        for _ in 0..remaining_len {
+            let eligible: Vec<char> = all_chars_pool
+                .iter()
+                .copied()
+                .filter(|c| {
+                    sets.iter().zip(&counts).all(|(set, &count)| {
+                        !set.chars.contains(c) || set.max.is_none_or(|max| count < max)
+                    })
+                })
+                .collect();
+            if eligible.is_empty() {
+                let maximum = password_chars.len();
+                password_chars.zeroize();
+                return Err(PassgenError::MaximumsBelowLength { maximum, length });
+            }
+
+            let c = *eligible.choose(rng).unwrap();
+            for (set, count) in sets.iter().zip(counts.iter_mut()) {
+                if set.chars.contains(&c) {
+                    *count += 1;
+                }
+            }
+            password_chars.push(c);
+        }
     }
 
     // Final shuffle for randomness
-    password_chars.shuffle(&mut rng);
-    password_chars.truncate(opts.length);
+    password_chars.shuffle(rng);
+    password_chars.truncate(length);
 
-    password_chars.into_iter().collect()
+    Ok(SecretString::from_chars(password_chars))
 }
 
 /// Calculate the entropy in bits for a given password length and character set size
@@ -138,66 +624,244 @@ pub fn calculate_entropy(length: usize, charset_size: usize) -> f64 {
     length as f64 * (charset_size as f64).log2()
 }
 
-/// Get the character sets used in the password options
-pub fn get_character_sets(opts: &PasswordOptions) -> Vec<String> {
-    let mut sets = Vec::new();
-    if opts.uppercase {
-        sets.push("uppercase".to_string());
-    }
-    if opts.lowercase {
-        sets.push("lowercase".to_string());
-    }
-    if opts.numbers {
-        sets.push("numbers".to_string());
+/// Entropy in bits of [`generate_password`] with these options; a policy's
+/// required prefix and suffix add nothing
+pub fn password_entropy(opts: &PasswordOptions) -> f64 {
+    let policy_opts = opts
+        .policy
+        .as_ref()
+        .and_then(|policy| apply_policy(opts, policy).ok());
+    let opts = policy_opts.as_ref().unwrap_or(opts);
+    calculate_constrained_entropy(opts, opts.length)
+        .unwrap_or_else(|| calculate_entropy(opts.length, get_charset_size(opts)))
+}
+
+/// Number of distinct passwords a generator with `entropy_bits` can produce
+pub fn keyspace_size(entropy_bits: f64) -> f64 {
+    entropy_bits.exp2().round()
+}
+
+/// Probability that `count` passwords drawn from a generator with
+/// `entropy_bits` contain at least one repeat (the birthday problem)
+pub fn collision_probability(count: usize, entropy_bits: f64) -> f64 {
+    let space = keyspace_size(entropy_bits);
+    let n = count as f64;
+    if count < 2 {
+        return 0.0;
     }
-    if opts.special {
-        sets.push("special".to_string());
+    if n > space {
+        return 1.0;
     }
-    sets
+    // ln P(no repeat) is the sum of ln(1 - i/space); when every term is tiny
+    // the closed form -n(n-1)/2space is exact to within n/space
+    let ln_distinct = if space > 1e6 * n {
+        -n * (n - 1.0) / 2.0 / space
+    } else {
+        (1..count).map(|i| (-(i as f64) / space).ln_1p()).sum()
+    };
+    -ln_distinct.exp_m1()
 }
 
-/// Calculate the total character set size based on options
-pub fn get_charset_size(opts: &PasswordOptions) -> usize {
-    let mut size = 0;
-    const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
-    const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
-    const NUMBERS: &str = "0123456789";
-    const SPECIAL: &str = "!@#$%^&*()-=_+[]{}|;:,.<>?";
-
-    let build_filtered = |set: &str, ambiguous: &[char]| -> usize {
-        if opts.avoid_ambiguous {
-            set.chars().filter(|c| !ambiguous.contains(c)).count()
-        } else {
-            set.len()
+/// Entropy in bits of the space of `length`-character passwords that satisfy
+/// the minimum and maximum counts of every selected set.
+///
+/// Returns `None` when no password of that length satisfies the constraints.
+/// Characters shared by several sets are counted towards the first one only.
+pub fn calculate_constrained_entropy(opts: &PasswordOptions, length: usize) -> Option<f64> {
+    let sets = build_character_sets(opts);
+    if sets.is_empty() {
+        return None;
+    }
+
+    // Partition the pool so each character belongs to exactly one class
+    let mut seen: Vec<char> = Vec::new();
+    let mut classes: Vec<(usize, usize, usize)> = Vec::with_capacity(sets.len());
+    for set in &sets {
+        let size = set.chars.iter().filter(|c| !seen.contains(c)).count();
+        seen.extend_from_slice(&set.chars);
+        if size > 0 {
+            let max = set.max.unwrap_or(length).min(length);
+            classes.push((size, set.min, max));
         }
-    };
+    }
 
-    const AMBIGUOUS_UPPER: &[char] = &['I', 'O'];
-    const AMBIGUOUS_LOWER: &[char] = &['l'];
-    const AMBIGUOUS_NUMBERS: &[char] = &['0', '1'];
+    // ln(k!) for k in 0..=length
+    let mut ln_factorial = vec![0.0_f64; length + 1];
+    for k in 1..=length {
+        ln_factorial[k] = ln_factorial[k - 1] + (k as f64).ln();
+    }
 
-    if opts.uppercase {
-        size += build_filtered(UPPERCASE, AMBIGUOUS_UPPER);
+    let unbounded = classes.iter().all(|&(_, _, max)| max == length);
+    if unbounded
+        && classes.len() <= MAX_CLOSED_FORM_CLASSES
+        && let Some(entropy) = minimums_entropy(&classes, length, &ln_factorial)
+    {
+        return Some(entropy);
     }
-    if opts.lowercase {
-        size += build_filtered(LOWERCASE, AMBIGUOUS_LOWER);
+
+    // ways[j]: ln of the sum over count assignments using j positions of
+    // prod(size^count / count!), so the total is length! * ways[length]
+    let mut ways = vec![f64::NEG_INFINITY; length + 1];
+    ways[0] = 0.0;
+    for &(size, min, max) in &classes {
+        let ln_size = (size as f64).ln();
+        let mut next = vec![f64::NEG_INFINITY; length + 1];
+        for used in 0..=length {
+            if ways[used] == f64::NEG_INFINITY {
+                continue;
+            }
+            for count in min..=max.min(length - used) {
+                let term = ways[used] + count as f64 * ln_size - ln_factorial[count];
+                next[used + count] = ln_add_exp(next[used + count], term);
+            }
+        }
+        ways = next;
     }
-    if opts.numbers {
-        size += build_filtered(NUMBERS, AMBIGUOUS_NUMBERS);
+
+    let total = ln_factorial[length] + ways[length];
+    total
+        .is_finite()
+        .then(|| (total / std::f64::consts::LN_2).max(0.0))
+}
+
+/// Most classes [`minimums_entropy`] handles; it visits every subset
+const MAX_CLOSED_FORM_CLASSES: usize = 12;
+
+/// [`calculate_constrained_entropy`] for `(size, min, max)` classes without a
+/// binding maximum, by inclusion-exclusion over the classes left short of
+/// their minimum.
+///
+/// With `pool` characters in all, the passwords are those of `pool^length`
+/// minus, for each set S of short classes, the ones where every class i in S
+/// has fewer than min_i characters (signs alternating with |S|). Those are
+/// `length!` times the x^length coefficient of
+/// `e^((pool - size_S) x) * prod(sum over c < min_i of size_i^c x^c / c!)`,
+/// so the cost grows with the minimums rather than with the length.
+///
+/// Returns `None` when cancellation leaves too little of `pool^length` for
+/// the result to be accurate; the caller then counts exactly.
+fn minimums_entropy(
+    classes: &[(usize, usize, usize)],
+    length: usize,
+    ln_factorial: &[f64],
+) -> Option<f64> {
+    let pool: usize = classes.iter().map(|&(size, _, _)| size).sum();
+    let ln_pool = (pool as f64).ln();
+
+    // Fraction of pool^length that satisfies every minimum
+    let mut fraction = 0.0;
+    for subset in 0..1usize << classes.len() {
+        // ln of the coefficients of the product of the short classes' series
+        let mut series = vec![0.0_f64];
+        let mut rest = pool;
+        for (i, &(size, min, _)) in classes.iter().enumerate() {
+            if subset & (1 << i) == 0 {
+                continue;
+            }
+            let ln_size = (size as f64).ln();
+            let mut product = vec![f64::NEG_INFINITY; series.len() + min.saturating_sub(1)];
+            for (degree, &coefficient) in series.iter().enumerate() {
+                for count in 0..min {
+                    let term = coefficient + count as f64 * ln_size - ln_factorial[count];
+                    product[degree + count] = ln_add_exp(product[degree + count], term);
+                }
+            }
+            series = product;
+            rest -= size;
+        }
+
+        let sign = if subset.count_ones() % 2 == 0 {
+            1.0
+        } else {
+            -1.0
+        };
+        let ln_rest = (rest as f64).ln();
+        for (degree, &coefficient) in series.iter().enumerate().take(length + 1) {
+            let free = length - degree;
+            // rest^0 is 1 even for an empty remainder
+            let ln_free = if free == 0 {
+                0.0
+            } else {
+                free as f64 * ln_rest
+            };
+            let ln_term = coefficient + ln_free + ln_factorial[length]
+                - ln_factorial[free]
+                - length as f64 * ln_pool;
+            fraction += sign * ln_term.exp();
+        }
     }
-    if opts.special {
-        size += build_filtered(SPECIAL, &[]);
+
+    (fraction > 1e-6)
+        .then(|| ((length as f64 * ln_pool + fraction.ln()) / std::f64::consts::LN_2).max(0.0))
+}
+
+fn ln_add_exp(a: f64, b: f64) -> f64 {
+    if a == f64::NEG_INFINITY {
+        return b;
     }
+    let (hi, lo) = if a > b { (a, b) } else { (b, a) };
+    hi + (lo - hi).exp().ln_1p()
+}
 
-    size
+/// Get the character sets used in the password options
+pub fn get_character_sets(opts: &PasswordOptions) -> Vec<String> {
+    build_character_sets(opts)
+        .into_iter()
+        .map(|set| set.name)
+        .collect()
+}
+
+/// Calculate the total character set size based on options
+pub fn get_charset_size(opts: &PasswordOptions) -> usize {
+    character_pool(&build_character_sets(opts)).len()
 }
 
 /// Analyze password strength using zxcvbn
+///
+/// # Panics
+///
+/// Panics when the password is empty; see [`try_analyze_password_strength`].
 pub fn analyze_password_strength(password: &str, opts: &PasswordOptions) -> PasswordStrength {
-    let entropy = calculate_entropy(password.len(), get_charset_size(opts));
+    try_analyze_password_strength(password, opts).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Analyze password strength using zxcvbn, returning an error for empty input
+pub fn try_analyze_password_strength(
+    password: &str,
+    opts: &PasswordOptions,
+) -> Result<PasswordStrength, PassgenError> {
+    analyze_password(password, opts, None)
+}
+
+/// [`try_analyze_password_strength`], crediting `entropy` bits when the caller
+/// already has them; a generator works them out once for all its passwords
+pub(crate) fn analyze_password(
+    password: &str,
+    opts: &PasswordOptions,
+    entropy: Option<f64>,
+) -> Result<PasswordStrength, PassgenError> {
+    // Only the generated part of a policy-compliant password carries entropy
+    let mut length = password.chars().count();
+    let policy_opts = opts
+        .policy
+        .as_ref()
+        .and_then(|policy| apply_policy(opts, policy).ok());
+    let opts = match &policy_opts {
+        Some(body_opts) => {
+            length = length.min(body_opts.length);
+            body_opts
+        }
+        None => opts,
+    };
+    let entropy = entropy.unwrap_or_else(|| {
+        calculate_constrained_entropy(opts, length)
+            .unwrap_or_else(|| calculate_entropy(length, get_charset_size(opts)))
+    });
     let character_sets = get_character_sets(opts);
 
-    let estimate = zxcvbn(password, &[]).unwrap();
+    let banned_words = opts.banned_words.as_ref();
+    let estimate = estimate_with_inputs(password, &opts.user_inputs, banned_words)?;
+    let explanation = Explanation::from_estimate(&estimate);
     let strength_score = estimate.score();
     let strength_label = match strength_score {
         0 => "Very Weak".to_string(),
@@ -208,23 +872,68 @@ pub fn analyze_password_strength(password: &str, opts: &PasswordOptions) -> Pass
         _ => "Unknown".to_string(),
     };
 
-    // For now, use a simplified crack time estimate based on entropy
-    let crack_time_seconds = estimate_crack_time_from_entropy(entropy);
-    let crack_time_display = format_crack_time(crack_time_seconds);
-
-    PasswordStrength {
-        password: password.to_string(),
+    let mut strength = PasswordStrength {
+        password: Some(SecretString::from(password)),
         entropy_bits: entropy,
         strength_score,
         strength_label,
-        crack_time_seconds,
-        crack_time_display,
+        crack_time_seconds: 0.0,
+        crack_time_display: String::new(),
+        crack_time_log10: 0.0,
         character_sets,
-    }
+        generator_entropy_bits: Some(entropy),
+        guesses_log10: explanation.guesses_log10,
+        warning: explanation.warning,
+        suggestions: explanation.suggestions,
+        matches: explanation.matches,
+        crack_times: Vec::new(),
+        breach_count: None,
+        banned_word: None,
+    };
+    strength.apply_attackers(&[]);
+    flag_banned_word(&mut strength, banned_words);
+    Ok(strength)
+}
+
+/// What strength analysis knows about the account besides the password
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisContext {
+    /// Words about the account owner (name, username, email, company) that
+    /// analysis treats as easy to guess
+    pub user_inputs: Vec<String>,
+    /// Terms analysis treats as trivially guessable, reported in `banned_word`
+    pub banned_words: Option<BannedWords>,
 }
 
 /// Analyze password strength by detecting actual character sets used
+///
+/// # Panics
+///
+/// Panics when the password is empty; see [`try_analyze_smart`].
+#[deprecated(note = "use `try_analyze_smart`")]
 pub fn analyze_password_strength_smart(password: &str) -> PasswordStrength {
+    try_analyze_smart(password, &AnalysisContext::default()).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Analyze password strength by detecting actual character sets used,
+/// scoring down passwords built from the context's user inputs or containing
+/// one of its banned words, and returning an error for empty input
+pub fn try_analyze_smart(
+    password: &str,
+    context: &AnalysisContext,
+) -> Result<PasswordStrength, PassgenError> {
+    analyze_smart(
+        password,
+        &context.user_inputs,
+        context.banned_words.as_ref(),
+    )
+}
+
+fn analyze_smart(
+    password: &str,
+    user_inputs: &[String],
+    banned_words: Option<&BannedWords>,
+) -> Result<PasswordStrength, PassgenError> {
     // Detect which character sets are actually used in the password
     let has_uppercase = password.chars().any(|c| c.is_ascii_uppercase());
     let has_lowercase = password.chars().any(|c| c.is_ascii_lowercase());
@@ -263,7 +972,8 @@ pub fn analyze_password_strength_smart(password: &str) -> PasswordStrength {
         character_sets.push("special".to_string());
     }
 
-    let estimate = zxcvbn(password, &[]).unwrap();
+    let estimate = estimate_with_inputs(password, user_inputs, banned_words)?;
+    let explanation = Explanation::from_estimate(&estimate);
     let strength_score = estimate.score();
     let strength_label = match strength_score {
         0 => "Very Weak".to_string(),
@@ -274,43 +984,78 @@ pub fn analyze_password_strength_smart(password: &str) -> PasswordStrength {
         _ => "Unknown".to_string(),
     };
 
-    // Use the calculated entropy for crack time estimate
-    let crack_time_seconds = estimate_crack_time_from_entropy(entropy);
-    let crack_time_display = format_crack_time(crack_time_seconds);
-
-    PasswordStrength {
-        password: password.to_string(),
+    let mut strength = PasswordStrength {
+        password: Some(SecretString::from(password)),
         entropy_bits: entropy,
         strength_score,
         strength_label,
-        crack_time_seconds,
-        crack_time_display,
+        crack_time_seconds: 0.0,
+        crack_time_display: String::new(),
+        crack_time_log10: 0.0,
         character_sets,
+        generator_entropy_bits: None,
+        guesses_log10: explanation.guesses_log10,
+        warning: explanation.warning,
+        suggestions: explanation.suggestions,
+        matches: explanation.matches,
+        crack_times: Vec::new(),
+        breach_count: None,
+        banned_word: None,
+    };
+    strength.apply_attackers(&[]);
+    flag_banned_word(&mut strength, banned_words);
+    Ok(strength)
+}
+
+/// Run zxcvbn with the user inputs, their parts and their letters and digits
+/// run together (e.g. "jane.doe@acme.com" also contributes "jane", "doe",
+/// "acme" and "janedoeacmecom") as extra dictionary words, along with any
+/// banned words
+fn estimate_with_inputs(
+    password: &str,
+    user_inputs: &[String],
+    banned_words: Option<&BannedWords>,
+) -> Result<Entropy, PassgenError> {
+    let mut words: Vec<String> = banned_words
+        .map(|banned| banned.words().to_vec())
+        .unwrap_or_default();
+    for input in user_inputs {
+        let input = input.trim().to_lowercase();
+        let parts = input
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|part| part.chars().count() >= 3 && *part != input)
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        let joined: String = input.chars().filter(|c| c.is_alphanumeric()).collect();
+        if joined != input && joined.chars().count() >= 3 {
+            words.push(joined);
+        }
+        if !input.is_empty() {
+            words.push(input);
+        }
+        words.extend(parts);
     }
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+
+    zxcvbn(password, &words).map_err(analysis_error)
 }
 
-/// Estimate crack time based on entropy (simplified calculation)
-fn estimate_crack_time_from_entropy(entropy: f64) -> f64 {
-    // Assume 1e10 guesses per second (typical for offline attacks)
-    let guesses_per_second = 1e10;
-    let total_guesses = 2.0_f64.powi(entropy as i32);
-    total_guesses / guesses_per_second
+/// Report a banned term, which outranks zxcvbn's own warning
+fn flag_banned_word(strength: &mut PasswordStrength, banned_words: Option<&BannedWords>) {
+    let Some(word) = banned_words
+        .zip(strength.password.as_deref())
+        .and_then(|(banned, password)| banned.find(password))
+    else {
+        return;
+    };
+    strength.warning = Some(format!("Contains the banned word \"{}\".", word));
+    strength.banned_word = Some(word.to_string());
 }
 
-/// Format crack time in human-readable format
-fn format_crack_time(seconds: f64) -> String {
-    if seconds < 1.0 {
-        "less than a second".to_string()
-    } else if seconds < 60.0 {
-        format!("{:.0} seconds", seconds)
-    } else if seconds < 3600.0 {
-        format!("{:.0} minutes", seconds / 60.0)
-    } else if seconds < 86400.0 {
-        format!("{:.0} hours", seconds / 3600.0)
-    } else if seconds < 31536000.0 {
-        format!("{:.0} days", seconds / 86400.0)
-    } else {
-        format!("{:.0} years", seconds / 31536000.0)
+fn analysis_error(err: ZxcvbnError) -> PassgenError {
+    match err {
+        ZxcvbnError::BlankPassword => PassgenError::EmptyPassword,
+        other => PassgenError::Analysis(other.to_string()),
     }
 }
 
@@ -320,128 +1065,411 @@ pub fn generate_password_with_strength(opts: &PasswordOptions) -> PasswordStreng
     analyze_password_strength(&password, opts)
 }
 
+/// Generate a single password with strength analysis, propagating errors
+pub fn try_generate_password_with_strength(
+    opts: &PasswordOptions,
+) -> Result<PasswordStrength, PassgenError> {
+    let password = try_generate_password(opts)?;
+    try_analyze_password_strength(&password, opts)
+}
+
 /// Generate multiple passwords with strength analysis
+///
+/// Every password is kept; use [`PasswordGenerator`] to stream large batches.
 pub fn generate_multiple_passwords(opts: &PasswordOptions, count: usize) -> PasswordAnalysis {
-    let mut passwords = Vec::with_capacity(count);
-    let mut total_entropy = 0.0;
-    let mut total_strength = 0.0;
+    let mut analysis = PasswordAnalysis::from_passwords(
+        (0..count)
+            .map(|_| generate_password_with_strength(opts))
+            .collect(),
+    );
+    analysis.collision_probability = Some(collision_probability(count, password_entropy(opts)));
+    analysis
+}
 
-    for _ in 0..count {
-        let pwd_strength = generate_password_with_strength(opts);
-        total_entropy += pwd_strength.entropy_bits;
-        total_strength += pwd_strength.strength_score as f64;
-        passwords.push(pwd_strength);
-    }
+/// Generate multiple passwords with strength analysis, propagating errors
+pub fn try_generate_multiple_passwords(
+    opts: &PasswordOptions,
+    count: usize,
+) -> Result<PasswordAnalysis, PassgenError> {
+    try_generate_multiple_passwords_with_rng(opts, count, &mut StdRng::from_entropy())
+}
 
-    let average_entropy = total_entropy / count as f64;
-    let average_strength_score = total_strength / count as f64;
+/// Generate multiple passwords with strength analysis drawing randomness from
+/// `rng`, propagating errors
+pub fn try_generate_multiple_passwords_with_rng<R: RngCore + CryptoRng>(
+    opts: &PasswordOptions,
+    count: usize,
+    rng: &mut R,
+) -> Result<PasswordAnalysis, PassgenError> {
+    collect_analysis(PasswordGenerator::with_rng(opts.clone(), rng)?, count)
+}
 
-    PasswordAnalysis {
-        passwords,
+/// Generate `count` distinct passwords with strength analysis, returning an
+/// error when the options allow fewer
+pub fn try_generate_unique_passwords(
+    opts: &PasswordOptions,
+    count: usize,
+) -> Result<PasswordAnalysis, PassgenError> {
+    try_generate_unique_passwords_with_rng(opts, count, &mut StdRng::from_entropy())
+}
+
+/// Generate `count` distinct passwords with strength analysis drawing
+/// randomness from `rng`, returning an error when the options allow fewer
+pub fn try_generate_unique_passwords_with_rng<R: RngCore + CryptoRng>(
+    opts: &PasswordOptions,
+    count: usize,
+    rng: &mut R,
+) -> Result<PasswordAnalysis, PassgenError> {
+    collect_analysis(
+        PasswordGenerator::with_rng(opts.clone(), rng)?.unique(count)?,
         count,
-        average_entropy,
-        average_strength_score,
-    }
+    )
+}
+
+fn collect_analysis<R: RngCore + CryptoRng>(
+    generator: PasswordGenerator<R>,
+    count: usize,
+) -> Result<PasswordAnalysis, PassgenError> {
+    let entropy = generator.entropy_bits();
+    let passwords = generator.analyzed().take(count).collect::<Result<_, _>>()?;
+    let mut analysis = PasswordAnalysis::from_passwords(passwords);
+    analysis.collision_probability = Some(collision_probability(count, entropy));
+    Ok(analysis)
+}
+
+/// Check that the options can produce a password without drawing any randomness
+pub(crate) fn validate_options(opts: &PasswordOptions) -> Result<(), PassgenError> {
+    let body_opts = match &opts.policy {
+        Some(policy) => apply_policy(opts, policy)?,
+        None => opts.clone(),
+    };
+    check_constraints(&build_character_sets(&body_opts), body_opts.length)
 }
 
 /// Generate a phonetic password (easier to remember)
-pub fn generate_phonetic_password(opts: &PasswordOptions) -> String {
-    const CONSONANTS: &str = "bcdfghjklmnpqrstvwxyz";
-    const VOWELS: &str = "aeiou";
-    const DIGITS: &str = "0123456789";
-    const SYMBOLS: &str = "!@#$%^&*";
-
-    let mut rng = StdRng::from_entropy();
-    let mut password = String::new();
-
-    // Generate phonetic pattern: alternating consonant-vowel
-    for i in 0..opts.length {
-        let char = if i % 2 == 0 {
-            // Consonant (lowercase)
-            let idx = rng.gen_range(0..CONSONANTS.len());
-            CONSONANTS.chars().nth(idx).unwrap()
-        } else {
-            // Vowel (lowercase)
-            let idx = rng.gen_range(0..VOWELS.len());
-            VOWELS.chars().nth(idx).unwrap()
-        };
-        password.push(char);
-    }
-
-    // Add some digits and symbols for strength if enabled
-    if opts.length >= 8 {
-        // Replace 2 random positions with digits if numbers are enabled
-        if opts.numbers {
-            for _ in 0..2.min(opts.length / 4) {
-                let pos = rng.gen_range(0..password.len());
-                let digit = DIGITS.chars().nth(rng.gen_range(0..DIGITS.len())).unwrap();
-                password = password
-                    .chars()
-                    .enumerate()
-                    .map(|(i, c)| if i == pos { digit } else { c })
-                    .collect();
+///
+/// Syllables are always lowercase, so `lowercase` is ignored; `uppercase`
+/// adds capitals, and `numbers` and `special` substitute a few characters.
+///
+/// # Panics
+///
+/// Panics for a length of 0 or when filtering leaves no consonants or vowels;
+/// see [`try_generate_phonetic_password`].
+pub fn generate_phonetic_password(opts: &PasswordOptions) -> SecretString {
+    generate_phonetic_password_with_rng(opts, &mut StdRng::from_entropy())
+}
+
+/// Generate a phonetic password drawing randomness from `rng`
+pub fn generate_phonetic_password_with_rng<R: RngCore + CryptoRng>(
+    opts: &PasswordOptions,
+    rng: &mut R,
+) -> SecretString {
+    try_generate_phonetic_password_with_rng(opts, rng).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Generate a phonetic password, returning an error for a length of 0 or when
+/// filtering leaves no consonants or vowels
+pub fn try_generate_phonetic_password(
+    opts: &PasswordOptions,
+) -> Result<SecretString, PassgenError> {
+    try_generate_phonetic_password_with_rng(opts, &mut StdRng::from_entropy())
+}
+
+/// Generate a phonetic password drawing randomness from `rng`, returning an
+/// error for a length of 0 or when filtering leaves no consonants or vowels.
+///
+/// Syllables alternate consonant and vowel. With `uppercase` a syllable may
+/// start with a capital; `numbers` and `special` substitute digits and
+/// `symbols` (or the default phonetic symbols) at random positions, and
+/// `avoid_ambiguous` and `exclude_chars` filter every pool.
+pub fn try_generate_phonetic_password_with_rng<R: RngCore + CryptoRng>(
+    opts: &PasswordOptions,
+    rng: &mut R,
+) -> Result<SecretString, PassgenError> {
+    if opts.length == 0 {
+        return Err(PassgenError::ZeroLength);
+    }
+    let pools = PhoneticPools::new(opts);
+    if pools.consonants.is_empty() || pools.vowels.is_empty() {
+        return Err(PassgenError::NoCharacterSets);
+    }
+
+    avoid_banned_words(opts, rng, |rng| Ok(pools.generate(opts.length, rng)))
+}
+
+/// Characters available to the phonetic generator after applying the options
+struct PhoneticPools {
+    /// Syllable starts, including capitals when uppercase is enabled
+    consonants: Vec<char>,
+    vowels: Vec<char>,
+    /// Empty unless numbers are enabled
+    digits: Vec<char>,
+    /// Empty unless special characters are enabled
+    symbols: Vec<char>,
+}
+
+impl PhoneticPools {
+    fn new(opts: &PasswordOptions) -> Self {
+        let pool =
+            |set: &str| -> Vec<char> { set.chars().filter(|&c| is_allowed(opts, c)).collect() };
+
+        let mut consonants = pool(PHONETIC_CONSONANTS);
+        if opts.uppercase {
+            consonants.extend(pool(&PHONETIC_CONSONANTS.to_ascii_uppercase()));
+        }
+        let symbols = match &opts.symbols {
+            Some(symbols) => {
+                let mut chars: Vec<char> = Vec::new();
+                for c in symbols.chars().filter(|&c| is_allowed(opts, c)) {
+                    if !chars.contains(&c) {
+                        chars.push(c);
+                    }
+                }
+                chars
             }
+            None => pool(PHONETIC_SYMBOLS),
+        };
+
+        PhoneticPools {
+            consonants,
+            vowels: pool(PHONETIC_VOWELS),
+            digits: if opts.numbers {
+                pool(PHONETIC_DIGITS)
+            } else {
+                Vec::new()
+            },
+            symbols: if opts.special { symbols } else { Vec::new() },
         }
+    }
 
-        // Add one symbol if special characters are enabled
-        if opts.special {
-            let pos = rng.gen_range(0..password.len());
-            let symbol = SYMBOLS
-                .chars()
-                .nth(rng.gen_range(0..SYMBOLS.len()))
-                .unwrap();
-            password = password
-                .chars()
-                .enumerate()
-                .map(|(i, c)| if i == pos { symbol } else { c })
-                .collect();
+    /// Number of digit and symbol substitutions a password of `length` receives
+    fn substitutions(&self, length: usize) -> (usize, usize) {
+        if length < 8 {
+            return (0, 0);
         }
+        let digits = if self.digits.is_empty() {
+            0
+        } else {
+            2.min(length / 4)
+        };
+        (digits, usize::from(!self.symbols.is_empty()))
     }
 
-    password
-}
+    fn generate<R: RngCore + CryptoRng>(&self, length: usize, rng: &mut R) -> SecretString {
+        // Generate phonetic pattern: alternating consonant-vowel
+        let mut password: Vec<char> = (0..length)
+            .map(|i| {
+                let pool = if i % 2 == 0 {
+                    &self.consonants
+                } else {
+                    &self.vowels
+                };
+                *pool.choose(rng).unwrap()
+            })
+            .collect();
 
-/// Generate a pattern-based password with specific constraints
-pub fn generate_pattern_password(pattern: &str, _opts: &PasswordOptions) -> String {
-    const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
-    const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
-    const NUMBERS: &str = "0123456789";
-    const SYMBOLS: &str = "!@#$%^&*()-=_+[]{}|;:,.<>?";
+        // Add some digits and symbols for strength if enabled
+        let (digits, symbols) = self.substitutions(length);
 
-    let mut rng = StdRng::from_entropy();
-    let mut password = String::new();
+        // Distinct positions, so substitutions never overwrite each other
+        let positions = index::sample(rng, password.len(), digits + symbols);
+        for (n, pos) in positions.into_iter().enumerate() {
+            let pool = if n < digits {
+                &self.digits
+            } else {
+                &self.symbols
+            };
+            password[pos] = *pool.choose(rng).unwrap();
+        }
 
-    for ch in pattern.chars() {
-        let char_set = match ch {
-            'U' => UPPERCASE,
-            'L' => LOWERCASE,
-            'D' => NUMBERS,
-            'S' => SYMBOLS,
-            _ => continue, // Skip unknown pattern characters
-        };
+        SecretString::from_chars(password)
+    }
+}
+
+/// Entropy in bits of [`generate_phonetic_password`] with these options.
+///
+/// Counts the consonant/vowel alternation (21 and 5 choices by default) rather
+/// than the 26 lowercase letters an observer would assume, plus the choice of
+/// positions and values for the digit and symbol substitutions.
+pub fn phonetic_entropy(opts: &PasswordOptions) -> f64 {
+    let length = opts.length;
+    if length == 0 {
+        return 0.0;
+    }
+    let pools = PhoneticPools::new(opts);
+    let log2_len = |pool: &Vec<char>| (pool.len().max(1) as f64).log2();
+    let letters = length.div_ceil(2) as f64 * log2_len(&pools.consonants)
+        + (length / 2) as f64 * log2_len(&pools.vowels);
+
+    let (digits, symbols) = pools.substitutions(length);
+    let substituted = digits + symbols;
+    // Each position is equally likely to be substituted, so on average this
+    // share of the letter entropy is replaced
+    let kept_letters = letters * (length - substituted) as f64 / length as f64;
 
-        let idx = rng.gen_range(0..char_set.len());
-        let character = char_set.chars().nth(idx).unwrap();
-        password.push(character);
+    // Unordered digit positions, then the symbol position among the rest
+    let mut positions = ln_binomial(length, digits);
+    if symbols > 0 {
+        positions += ((length - digits) as f64).ln();
     }
 
-    password
+    kept_letters
+        + positions / std::f64::consts::LN_2
+        + digits as f64 * log2_len(&pools.digits)
+        + symbols as f64 * log2_len(&pools.symbols)
+}
+
+fn ln_binomial(n: usize, k: usize) -> f64 {
+    (0..k).map(|i| ((n - i) as f64 / (i + 1) as f64).ln()).sum()
+}
+
+/// Generate a pattern-based password; see [`Pattern`] for the template syntax
+///
+/// # Panics
+///
+/// Panics when the pattern is invalid; see [`try_generate_pattern_password`].
+pub fn generate_pattern_password(pattern: &str, opts: &PasswordOptions) -> SecretString {
+    generate_pattern_password_with_rng(pattern, opts, &mut StdRng::from_entropy())
+}
+
+/// Generate a pattern-based password drawing randomness from `rng`
+pub fn generate_pattern_password_with_rng<R: RngCore + CryptoRng>(
+    pattern: &str,
+    opts: &PasswordOptions,
+    rng: &mut R,
+) -> SecretString {
+    Pattern::parse_with_options(pattern, opts)
+        .unwrap_or_else(|err| panic!("{}", err))
+        .generate(rng)
+}
+
+/// Entropy in bits of [`generate_pattern_password`] for `pattern`, or 0 for
+/// an invalid pattern; see [`Pattern::entropy`]
+pub fn pattern_entropy(pattern: &str, opts: &PasswordOptions) -> f64 {
+    Pattern::parse_with_options(pattern, opts).map_or(0.0, |pattern| pattern.entropy())
+}
+
+/// Generate a pattern-based password, returning an error when the pattern is
+/// invalid or contains no random elements
+pub fn try_generate_pattern_password(
+    pattern: &str,
+    opts: &PasswordOptions,
+) -> Result<SecretString, PassgenError> {
+    try_generate_pattern_password_with_rng(pattern, opts, &mut StdRng::from_entropy())
+}
+
+/// Generate a pattern-based password drawing randomness from `rng`, returning
+/// an error when the pattern is invalid or contains no random elements
+pub fn try_generate_pattern_password_with_rng<R: RngCore + CryptoRng>(
+    pattern: &str,
+    opts: &PasswordOptions,
+    rng: &mut R,
+) -> Result<SecretString, PassgenError> {
+    let pattern = Pattern::parse_with_options(pattern, opts)?;
+    if pattern.entropy() == 0.0 {
+        return Err(PassgenError::EmptyPattern);
+    }
+    avoid_banned_words(opts, rng, |rng| Ok(pattern.generate(rng)))
 }
 
 /// Smart password generation with different modes
+#[derive(Clone, Debug)]
 pub enum SmartPasswordMode {
     Phonetic,
     Pattern(String),
+    Passphrase(PassphraseOptions),
 }
 
 pub fn generate_smart_password(
     mode: SmartPasswordMode,
     opts: &PasswordOptions,
 ) -> PasswordStrength {
-    let password = match mode {
-        SmartPasswordMode::Phonetic => generate_phonetic_password(opts),
-        SmartPasswordMode::Pattern(pattern) => generate_pattern_password(&pattern, opts),
+    try_generate_smart_password(mode, opts).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Smart password generation, propagating pattern and analysis errors
+pub fn try_generate_smart_password(
+    mode: SmartPasswordMode,
+    opts: &PasswordOptions,
+) -> Result<PasswordStrength, PassgenError> {
+    try_generate_smart_password_with_rng(mode, opts, &mut StdRng::from_entropy())
+}
+
+/// Smart password generation drawing randomness from `rng`, propagating
+/// pattern and analysis errors
+pub fn try_generate_smart_password_with_rng<R: RngCore + CryptoRng>(
+    mode: SmartPasswordMode,
+    opts: &PasswordOptions,
+    rng: &mut R,
+) -> Result<PasswordStrength, PassgenError> {
+    let password = match &mode {
+        SmartPasswordMode::Phonetic => try_generate_phonetic_password_with_rng(opts, rng)?,
+        SmartPasswordMode::Pattern(pattern) => {
+            try_generate_pattern_password_with_rng(pattern, opts, rng)?
+        }
+        SmartPasswordMode::Passphrase(phrase_opts) => {
+            if phrase_opts.words == 0 {
+                return Err(PassgenError::NoWords);
+            }
+            avoid_banned_words(opts, rng, |rng| {
+                Ok(generate_passphrase_with_rng(phrase_opts, rng))
+            })?
+        }
+    };
+    analyze_smart_password(&password, &mode, opts, smart_entropy(&mode, opts))
+}
+
+/// Entropy in bits of a smart generator's passwords
+pub(crate) fn smart_entropy(mode: &SmartPasswordMode, opts: &PasswordOptions) -> f64 {
+    match mode {
+        SmartPasswordMode::Phonetic => phonetic_entropy(opts),
+        SmartPasswordMode::Pattern(pattern) => pattern_entropy(pattern, opts),
+        SmartPasswordMode::Passphrase(phrase_opts) => passphrase_entropy(phrase_opts),
+    }
+}
+
+/// Analyze a password from a smart generator, crediting the generator's
+/// `generator_entropy` rather than the characters observed
+pub(crate) fn analyze_smart_password(
+    password: &str,
+    mode: &SmartPasswordMode,
+    opts: &PasswordOptions,
+    generator_entropy: f64,
+) -> Result<PasswordStrength, PassgenError> {
+    // Optional elements can leave a pattern's output empty, which zxcvbn
+    // rejects; it is simply the first guess an attacker would try
+    let mut strength = if password.is_empty() {
+        empty_password_strength()
+    } else {
+        analyze_smart(password, &opts.user_inputs, opts.banned_words.as_ref())?
     };
+    // The characters of a passphrase say little about its words
+    if let SmartPasswordMode::Passphrase(_) = mode {
+        strength.entropy_bits = generator_entropy;
+    }
+    strength.generator_entropy_bits = Some(generator_entropy);
+    strength.apply_attackers(&[]);
+    Ok(strength)
+}
 
-    analyze_password_strength_smart(&password)
+/// Analysis of a generated empty password, found with a single guess
+fn empty_password_strength() -> PasswordStrength {
+    PasswordStrength {
+        password: Some(SecretString::new()),
+        entropy_bits: 0.0,
+        strength_score: 0,
+        strength_label: "Very Weak".to_string(),
+        crack_time_seconds: 0.0,
+        crack_time_display: String::new(),
+        crack_time_log10: 0.0,
+        character_sets: Vec::new(),
+        generator_entropy_bits: None,
+        guesses_log10: 0.0,
+        warning: None,
+        suggestions: Vec::new(),
+        matches: Vec::new(),
+        crack_times: Vec::new(),
+        breach_count: None,
+        banned_word: None,
+    }
 }
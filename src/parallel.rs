@@ -0,0 +1,137 @@
+use rayon::iter::{IntoParallelIterator, ParallelBridge, ParallelIterator};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver};
+use std::vec;
+
+use crate::generator::Distinct;
+use crate::{AnalysisContext, PassgenError, PasswordStrength, SecretString, try_analyze_smart};
+
+/// Work handed to each thread at a time; large enough to amortize the
+/// hand-off, small enough to keep memory flat and output flowing
+const ITEMS_PER_THREAD: usize = 64;
+
+/// How bulk generation and analysis are spread over threads
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Parallelism {
+    /// Worker threads; 0 uses one per CPU
+    pub jobs: usize,
+    /// Yield results in input order rather than as soon as each is ready
+    pub ordered: bool,
+}
+
+/// Results computed on a thread pool, from
+/// [`PasswordGenerator::parallel`](crate::PasswordGenerator::parallel) or
+/// [`analyze_parallel`].
+///
+/// Only a bounded number of results are computed ahead of the consumer;
+/// dropping the iterator stops the workers.
+pub struct ParallelResults {
+    inner: Inner,
+    pub(crate) distinct: Option<Distinct>,
+}
+
+type Item = Result<PasswordStrength, PassgenError>;
+
+enum Inner {
+    Ordered {
+        refill: Box<dyn FnMut() -> Vec<Item> + Send>,
+        buffer: vec::IntoIter<Item>,
+    },
+    Unordered(Receiver<Item>),
+}
+
+impl Iterator for ParallelResults {
+    type Item = Item;
+
+    fn next(&mut self) -> Option<Item> {
+        let Some(distinct) = &mut self.distinct else {
+            return self.inner.next();
+        };
+        for _ in 0..distinct.attempts {
+            match self.inner.next()? {
+                Ok(strength)
+                    if !distinct.insert(strength.password.as_deref().unwrap_or_default()) =>
+                {
+                    continue;
+                }
+                result => return Some(result),
+            }
+        }
+        Some(Err(distinct.exhausted()))
+    }
+}
+
+impl Inner {
+    fn next(&mut self) -> Option<Item> {
+        match self {
+            Inner::Ordered { refill, buffer } => buffer.next().or_else(|| {
+                *buffer = refill().into_iter();
+                buffer.next()
+            }),
+            Inner::Unordered(receiver) => receiver.recv().ok(),
+        }
+    }
+}
+
+/// Analyze `passwords` on a thread pool with [`try_analyze_smart`]
+pub fn analyze_parallel<I>(
+    passwords: I,
+    context: AnalysisContext,
+    parallelism: Parallelism,
+) -> ParallelResults
+where
+    I: IntoIterator<Item = SecretString>,
+    I::IntoIter: Send + 'static,
+{
+    run(
+        passwords.into_iter(),
+        parallelism,
+        move |password: SecretString| try_analyze_smart(&password, &context),
+    )
+}
+
+/// Apply `work` to every input on a dedicated pool
+pub(crate) fn run<W, I, F>(input: I, parallelism: Parallelism, work: F) -> ParallelResults
+where
+    W: Send + 'static,
+    I: Iterator<Item = W> + Send + 'static,
+    F: Fn(W) -> Item + Send + Sync + 'static,
+{
+    let pool = pool(parallelism.jobs);
+    let ahead = pool.current_num_threads() * ITEMS_PER_THREAD;
+    let work = Arc::new(work);
+
+    let inner = if parallelism.ordered {
+        let mut input = input;
+        Inner::Ordered {
+            refill: Box::new(move || {
+                let batch: Vec<W> = input.by_ref().take(ahead).collect();
+                pool.install(|| batch.into_par_iter().map(|w| work(w)).collect())
+            }),
+            buffer: Vec::new().into_iter(),
+        }
+    } else {
+        // The bounded channel holds workers back once they are `ahead` of
+        // the consumer; a failed send means it has gone away
+        let (sender, receiver) = mpsc::sync_channel(ahead);
+        pool.spawn(move || {
+            let _ = input
+                .par_bridge()
+                .try_for_each_with(sender, |sender, w| sender.send(work(w)).map_err(drop));
+        });
+        Inner::Unordered(receiver)
+    };
+    ParallelResults {
+        inner,
+        distinct: None,
+    }
+}
+
+fn pool(jobs: usize) -> ThreadPool {
+    ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .thread_name(|i| format!("passgen-{}", i))
+        .build()
+        .expect("failed to start worker threads")
+}
@@ -0,0 +1,254 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{CryptoRng, Rng, RngCore, SeedableRng};
+use std::path::Path;
+use std::str::FromStr;
+use std::{fs, io};
+
+use crate::{AnalysisContext, PassgenError, PasswordStrength, SecretString, try_analyze_smart};
+
+const BIP39_ENGLISH: &str = include_str!("../wordlists/bip39_english.txt");
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!@#$%^&*";
+
+/// A list of distinct words to draw passphrase words from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Wordlist {
+    words: Vec<String>,
+}
+
+impl Wordlist {
+    /// The bundled BIP-39 English wordlist (2048 words, 11 bits per word).
+    ///
+    /// The EFF large and short lists are not bundled yet; load them with
+    /// [`Wordlist::from_file`], which reads their dice-numbered format.
+    pub fn bip39_english() -> Self {
+        Self::parse(BIP39_ENGLISH).expect("bundled wordlist is valid")
+    }
+
+    /// Parse a wordlist from text, one word per line.
+    ///
+    /// Diceware-style lines such as `11111\tabacus` (the EFF list format) are
+    /// accepted, blank lines and `#` comments are skipped and duplicates are
+    /// dropped. Returns `None` when fewer than two distinct words remain.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut words: Vec<String> = Vec::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut tokens = line.split_whitespace();
+            let first = tokens.next().unwrap_or_default();
+            let word = match tokens.next() {
+                Some(second) if first.chars().all(|c| c.is_ascii_digit()) => second,
+                _ => first,
+            };
+            if !words.iter().any(|w| w == word) {
+                words.push(word.to_string());
+            }
+        }
+
+        if words.len() < 2 {
+            return None;
+        }
+        Some(Self { words })
+    }
+
+    /// Load a user-supplied wordlist file (see [`Wordlist::parse`] for the format).
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "wordlist must contain at least two distinct words",
+            )
+        })
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Entropy contributed by each word drawn uniformly from the list.
+    pub fn entropy_per_word(&self) -> f64 {
+        (self.words.len() as f64).log2()
+    }
+}
+
+/// How the words of a passphrase are capitalized.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Capitalization {
+    #[default]
+    Lower,
+    Upper,
+    Title,
+    /// Each word is independently title-cased with probability 1/2.
+    Random,
+}
+
+impl FromStr for Capitalization {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "lower" => Ok(Capitalization::Lower),
+            "upper" => Ok(Capitalization::Upper),
+            "title" => Ok(Capitalization::Title),
+            "random" => Ok(Capitalization::Random),
+            other => Err(format!(
+                "unknown capitalization '{}' (expected lower, upper, title or random)",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PassphraseOptions {
+    pub words: usize,
+    pub separator: String,
+    pub capitalization: Capitalization,
+    /// Append a random digit to a random word
+    pub digit: bool,
+    /// Append a random symbol to a random word
+    pub symbol: bool,
+    pub wordlist: Wordlist,
+}
+
+impl Default for PassphraseOptions {
+    fn default() -> Self {
+        Self {
+            words: 6,
+            separator: "-".to_string(),
+            capitalization: Capitalization::Lower,
+            digit: false,
+            symbol: false,
+            wordlist: Wordlist::bip39_english(),
+        }
+    }
+}
+
+/// Generate a diceware-style passphrase from the configured wordlist.
+pub fn generate_passphrase(opts: &PassphraseOptions) -> SecretString {
+    generate_passphrase_with_rng(opts, &mut StdRng::from_entropy())
+}
+
+/// Generate a passphrase drawing randomness from `rng`
+pub fn generate_passphrase_with_rng<R: RngCore + CryptoRng>(
+    opts: &PassphraseOptions,
+    rng: &mut R,
+) -> SecretString {
+    let mut words: Vec<SecretString> = Vec::with_capacity(opts.words);
+
+    for _ in 0..opts.words {
+        let word = opts
+            .wordlist
+            .words()
+            .choose(rng)
+            .expect("wordlist is never empty");
+        let word = match opts.capitalization {
+            Capitalization::Lower => word.to_lowercase(),
+            Capitalization::Upper => word.to_uppercase(),
+            Capitalization::Title => title_case(word),
+            Capitalization::Random => {
+                if rng.gen_bool(0.5) {
+                    title_case(word)
+                } else {
+                    word.to_lowercase()
+                }
+            }
+        };
+        words.push(SecretString::from(word));
+    }
+
+    if !words.is_empty() {
+        if opts.digit {
+            let pos = rng.gen_range(0..words.len());
+            let digit = DIGITS.as_bytes()[rng.gen_range(0..DIGITS.len())] as char;
+            words[pos].push(digit);
+        }
+        if opts.symbol {
+            let pos = rng.gen_range(0..words.len());
+            let symbol = SYMBOLS.as_bytes()[rng.gen_range(0..SYMBOLS.len())] as char;
+            words[pos].push(symbol);
+        }
+    }
+
+    let mut passphrase = SecretString::new();
+    for (i, word) in words.iter().enumerate() {
+        if i > 0 {
+            passphrase.push_str(&opts.separator);
+        }
+        passphrase.push_str(word);
+    }
+    passphrase
+}
+
+/// Entropy of the passphrase generator: words × log2(list size), plus the
+/// choices made by random capitalization and digit/symbol injection.
+pub fn passphrase_entropy(opts: &PassphraseOptions) -> f64 {
+    if opts.words == 0 {
+        return 0.0;
+    }
+
+    let words = opts.words as f64;
+    let mut entropy = words * opts.wordlist.entropy_per_word();
+    if opts.capitalization == Capitalization::Random {
+        entropy += words;
+    }
+    if opts.digit {
+        entropy += (words * DIGITS.len() as f64).log2();
+    }
+    if opts.symbol {
+        entropy += (words * SYMBOLS.len() as f64).log2();
+    }
+    entropy
+}
+
+/// Analyze a passphrase produced with `opts`, reporting the generator entropy
+/// rather than the per-character estimate.
+///
+/// # Panics
+///
+/// Panics when the passphrase is empty; see [`try_analyze_passphrase_strength`].
+pub fn analyze_passphrase_strength(passphrase: &str, opts: &PassphraseOptions) -> PasswordStrength {
+    try_analyze_passphrase_strength(passphrase, opts).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Analyze a passphrase produced with `opts`, returning an error for empty input
+pub fn try_analyze_passphrase_strength(
+    passphrase: &str,
+    opts: &PassphraseOptions,
+) -> Result<PasswordStrength, PassgenError> {
+    let mut strength = try_analyze_smart(passphrase, &AnalysisContext::default())?;
+    strength.entropy_bits = passphrase_entropy(opts);
+    strength.generator_entropy_bits = Some(strength.entropy_bits);
+    strength.apply_attackers(&[]);
+    Ok(strength)
+}
+
+/// Generate a single passphrase with strength analysis
+pub fn generate_passphrase_with_strength(opts: &PassphraseOptions) -> PasswordStrength {
+    let passphrase = generate_passphrase(opts);
+    analyze_passphrase_strength(&passphrase, opts)
+}
+
+fn title_case(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
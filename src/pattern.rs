@@ -0,0 +1,463 @@
+use rand::seq::SliceRandom;
+use rand::{CryptoRng, Rng, RngCore};
+use std::f64::consts::LN_2;
+use std::str::FromStr;
+
+use crate::{
+    LOWERCASE, NUMBERS, PassgenError, PasswordOptions, SPECIAL, SecretString, UPPERCASE, Wordlist,
+    is_allowed, ln_add_exp,
+};
+
+const HEX_UPPER: &str = "0123456789ABCDEF";
+const HEX_LOWER: &str = "0123456789abcdef";
+const BASE32: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Largest count accepted in a `{n}` or `{m,n}` repetition
+const MAX_REPEAT: usize = 4096;
+
+/// Most characters a pattern may produce; nested repetitions multiply, so
+/// each count staying under [`MAX_REPEAT`] is not enough
+const MAX_LENGTH: usize = 65_536;
+
+/// A parsed password template.
+///
+/// | Syntax | Meaning |
+/// |--------|---------|
+/// | `U` `L` `D` `S` | uppercase letter, lowercase letter, digit, symbol |
+/// | `A` | letter or digit |
+/// | `H` `h` | uppercase / lowercase hexadecimal digit |
+/// | `B` | base32 character (RFC 4648: `A-Z`, `2-7`) |
+/// | `W` | word from the bundled wordlist |
+/// | `[A-F0-9]` | custom class; `\` escapes `]`, `-` and `\` |
+/// | `\x`, `'text'`, `"text"` | literals |
+/// | `-`, `_`, `.`, ... | bare punctuation is literal |
+/// | `X{n}`, `X{m,n}` | repeat the preceding element n, or m to n, times |
+/// | `(...)`, `X?` | group, optional element |
+///
+/// For example `[A-Z0-9]{4}('-'[A-Z0-9]{4}){2}` produces license keys like
+/// `7KQ2-0ZPA-M3XD`.
+///
+/// The template decides which classes appear, so the `uppercase`, `lowercase`,
+/// `numbers` and `special` flags do not apply; see
+/// [`Pattern::parse_with_options`] for the options that do.
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    items: Vec<Item>,
+    wordlist: Option<Wordlist>,
+}
+
+#[derive(Clone, Debug)]
+struct Item {
+    node: Node,
+    min: usize,
+    max: usize,
+    optional: bool,
+    /// Most characters the item can produce
+    max_length: usize,
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Literal(String),
+    Class(Vec<char>),
+    Word,
+    Group(Vec<Item>),
+}
+
+impl Pattern {
+    /// Parse a template, reporting the character position of the first error
+    pub fn parse(pattern: &str) -> Result<Self, PassgenError> {
+        Self::parse_with_options(pattern, &PasswordOptions::default())
+    }
+
+    /// Parse a template whose classes follow `opts`: `S` draws from `symbols`
+    /// when set, and `avoid_ambiguous` and `exclude_chars` filter every class
+    /// and the words for `W`. Literals are kept as written. A class emptied by
+    /// filtering is an error at its position.
+    pub fn parse_with_options(pattern: &str, opts: &PasswordOptions) -> Result<Self, PassgenError> {
+        let mut parser = Parser {
+            chars: pattern.chars().collect(),
+            pos: 0,
+            opts,
+            word_position: None,
+            word_length: 0,
+        };
+        let items = parser.sequence(false)?;
+        let wordlist = match parser.word_position {
+            Some(position) => Some(filtered_wordlist(opts).ok_or_else(|| {
+                parser.error(
+                    position,
+                    "no words left after filtering excluded characters",
+                )
+            })?),
+            None => None,
+        };
+        Ok(Pattern { items, wordlist })
+    }
+
+    /// Generate a password from the template
+    pub fn generate<R: RngCore + CryptoRng>(&self, rng: &mut R) -> SecretString {
+        let mut password = SecretString::new();
+        self.generate_items(&self.items, rng, &mut password);
+        password
+    }
+
+    /// Entropy in bits of [`Pattern::generate`] as it samples.
+    ///
+    /// Repetition counts are drawn uniformly and optional elements are a coin
+    /// flip, so short outputs are more likely than their share of the
+    /// keyspace: `D{0,8}` is empty one time in nine. Each count is weighted
+    /// by its probability, giving 16.5 bits for `D{0,8}` where its
+    /// [`Pattern::keyspace_bits`] is 26.7.
+    pub fn entropy(&self) -> f64 {
+        self.items_entropy(&self.items)
+    }
+
+    /// log2 of the number of passwords the pattern can produce.
+    ///
+    /// Variable repetitions and optional elements count the outputs of every
+    /// length they allow, so `D?` has 11 and `D{1,2}` has 110, assuming
+    /// outputs reached through different choices do not coincide. This bounds
+    /// how many distinct passwords exist, not how hard one is to guess; see
+    /// [`Pattern::entropy`] for that.
+    pub fn keyspace_bits(&self) -> f64 {
+        self.items_keyspace(&self.items)
+    }
+
+    fn generate_items<R: RngCore + CryptoRng>(
+        &self,
+        items: &[Item],
+        rng: &mut R,
+        password: &mut SecretString,
+    ) {
+        for item in items {
+            if item.optional && !rng.gen_bool(0.5) {
+                continue;
+            }
+            let count = if item.min == item.max {
+                item.min
+            } else {
+                rng.gen_range(item.min..=item.max)
+            };
+            for _ in 0..count {
+                match &item.node {
+                    Node::Literal(text) => password.push_str(text),
+                    Node::Class(chars) => password.push(*chars.choose(rng).unwrap()),
+                    Node::Word => {
+                        let words = self.wordlist.as_ref().expect("parsed with words").words();
+                        password.push_str(words.choose(rng).unwrap());
+                    }
+                    Node::Group(items) => self.generate_items(items, rng, password),
+                }
+            }
+        }
+    }
+
+    fn node_bits(&self, node: &Node, group_bits: impl Fn(&[Item]) -> f64) -> f64 {
+        match node {
+            Node::Literal(_) => 0.0,
+            Node::Class(chars) => (chars.len() as f64).log2(),
+            Node::Word => self
+                .wordlist
+                .as_ref()
+                .map_or(0.0, Wordlist::entropy_per_word),
+            Node::Group(items) => group_bits(items),
+        }
+    }
+
+    fn items_entropy(&self, items: &[Item]) -> f64 {
+        items
+            .iter()
+            .map(|item| {
+                let node = self.node_bits(&item.node, |items| self.items_entropy(items));
+                // The uniform count, then each repetition in turn
+                let counts = (item.max - item.min + 1) as f64;
+                let mean_count = (item.min + item.max) as f64 / 2.0;
+                let repeated = counts.log2() + mean_count * node;
+                if item.optional {
+                    // The coin flip, then the element half of the time
+                    1.0 + repeated / 2.0
+                } else {
+                    repeated
+                }
+            })
+            .sum()
+    }
+
+    fn items_keyspace(&self, items: &[Item]) -> f64 {
+        items
+            .iter()
+            .map(|item| {
+                let node = self.node_bits(&item.node, |items| self.items_keyspace(items));
+                // ln of the sum over allowed counts k of 2^(node * k)
+                let mut outputs = (item.min..=item.max)
+                    .map(|count| node * count as f64 * LN_2)
+                    .fold(f64::NEG_INFINITY, ln_add_exp);
+                if item.optional {
+                    outputs = ln_add_exp(outputs, 0.0);
+                }
+                outputs / LN_2
+            })
+            .sum()
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = PassgenError;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        Pattern::parse(pattern)
+    }
+}
+
+/// The bundled wordlist without words containing filtered characters
+fn filtered_wordlist(opts: &PasswordOptions) -> Option<Wordlist> {
+    let wordlist = Wordlist::bip39_english();
+    if !opts.avoid_ambiguous && opts.exclude_chars.is_empty() {
+        return Some(wordlist);
+    }
+    let words: Vec<&str> = wordlist
+        .words()
+        .iter()
+        .map(String::as_str)
+        .filter(|word| word.chars().all(|c| is_allowed(opts, c)))
+        .collect();
+    Wordlist::parse(&words.join("\n"))
+}
+
+struct Parser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    opts: &'a PasswordOptions,
+    /// Position of the first `W`, if any
+    word_position: Option<usize>,
+    /// Length of the longest word `W` can produce, once one is seen
+    word_length: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, position: usize, message: impl Into<String>) -> PassgenError {
+        PassgenError::InvalidPattern {
+            position,
+            message: message.into(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    /// Parse items up to the end of input, or up to `)` inside a group
+    fn sequence(&mut self, in_group: bool) -> Result<Vec<Item>, PassgenError> {
+        let mut items = Vec::new();
+        let mut length = 0usize;
+        while let Some(c) = self.peek() {
+            if c == ')' {
+                if in_group {
+                    return Ok(items);
+                }
+                return Err(self.error(self.pos, "unmatched ')'"));
+            }
+            let start = self.pos;
+            let node = self.atom()?;
+            let item = self.quantifiers(node)?;
+            length = length.saturating_add(item.max_length);
+            if length > MAX_LENGTH {
+                return Err(self.error(
+                    start,
+                    format!("pattern can produce more than {} characters", MAX_LENGTH),
+                ));
+            }
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    fn atom(&mut self) -> Result<Node, PassgenError> {
+        let start = self.pos;
+        let c = self.peek().expect("called with input left");
+        self.pos += 1;
+        let class = |set: &str| self.class(start, set.chars().collect());
+        match c {
+            'U' => class(UPPERCASE),
+            'L' => class(LOWERCASE),
+            'D' => class(NUMBERS),
+            'S' => class(self.opts.symbols.as_deref().unwrap_or(SPECIAL)),
+            'A' => class(&[UPPERCASE, LOWERCASE, NUMBERS].concat()),
+            'H' => class(HEX_UPPER),
+            'h' => class(HEX_LOWER),
+            'B' => class(BASE32),
+            'W' => {
+                if self.word_position.is_none() {
+                    self.word_position = Some(start);
+                    self.word_length = Wordlist::bip39_english()
+                        .words()
+                        .iter()
+                        .map(|word| word.chars().count())
+                        .max()
+                        .unwrap_or(0);
+                }
+                Ok(Node::Word)
+            }
+            '[' => self.custom_class(start),
+            '(' => {
+                let items = self.sequence(true)?;
+                if self.peek() != Some(')') {
+                    return Err(self.error(start, "unclosed '('"));
+                }
+                self.pos += 1;
+                Ok(Node::Group(items))
+            }
+            '\\' => match self.peek() {
+                Some(escaped) => {
+                    self.pos += 1;
+                    Ok(Node::Literal(escaped.to_string()))
+                }
+                None => Err(self.error(start, "dangling '\\' at end of pattern")),
+            },
+            '\'' | '"' => {
+                let end = self.chars[self.pos..]
+                    .iter()
+                    .position(|&q| q == c)
+                    .ok_or_else(|| self.error(start, format!("unclosed {} quote", c)))?;
+                let text: String = self.chars[self.pos..self.pos + end].iter().collect();
+                self.pos += end + 1;
+                Ok(Node::Literal(text))
+            }
+            '{' | '?' => Err(self.error(start, format!("'{}' must follow an element", c))),
+            ']' | '}' => Err(self.error(start, format!("unmatched '{}'", c))),
+            c if c.is_alphanumeric() => Err(self.error(
+                start,
+                format!(
+                    "unknown class '{}'; escape it (\\{}) or quote it for a literal",
+                    c, c
+                ),
+            )),
+            // Remaining punctuation and whitespace are literals
+            c => Ok(Node::Literal(c.to_string())),
+        }
+    }
+
+    /// Parse the inside of `[...]`; the opening bracket is already consumed
+    fn custom_class(&mut self, start: usize) -> Result<Node, PassgenError> {
+        let mut chars: Vec<char> = Vec::new();
+        while let Some(c) = self.next_class_char(start)? {
+            let is_range =
+                self.peek() == Some('-') && self.chars.get(self.pos + 1).is_some_and(|&n| n != ']');
+            if !is_range {
+                chars.push(c);
+                continue;
+            }
+
+            let dash = self.pos;
+            self.pos += 1;
+            let Some(end) = self.next_class_char(start)? else {
+                unreachable!("checked for ']' above");
+            };
+            if end < c {
+                return Err(self.error(dash, format!("invalid range '{}-{}'", c, end)));
+            }
+            chars.extend(c..=end);
+        }
+
+        if chars.is_empty() {
+            return Err(self.error(start, "empty character class"));
+        }
+        self.class(start, chars)
+    }
+
+    /// A class node with duplicates and filtered characters removed
+    fn class(&self, start: usize, chars: Vec<char>) -> Result<Node, PassgenError> {
+        let mut unique: Vec<char> = Vec::with_capacity(chars.len());
+        for c in chars {
+            if is_allowed(self.opts, c) && !unique.contains(&c) {
+                unique.push(c);
+            }
+        }
+        if unique.is_empty() {
+            return Err(self.error(
+                start,
+                "no characters left in class after filtering excluded characters",
+            ));
+        }
+        Ok(Node::Class(unique))
+    }
+
+    /// Next (possibly escaped) class member, or `None` at the closing `]`
+    fn next_class_char(&mut self, start: usize) -> Result<Option<char>, PassgenError> {
+        match self.peek() {
+            None => Err(self.error(start, "unclosed '['")),
+            Some(']') => {
+                self.pos += 1;
+                Ok(None)
+            }
+            Some('\\') => {
+                let escaped = self
+                    .chars
+                    .get(self.pos + 1)
+                    .copied()
+                    .ok_or_else(|| self.error(start, "unclosed '['"))?;
+                self.pos += 2;
+                Ok(Some(escaped))
+            }
+            Some(c) => {
+                self.pos += 1;
+                Ok(Some(c))
+            }
+        }
+    }
+
+    /// Parse an optional `{n}` / `{m,n}` repetition followed by an optional `?`
+    fn quantifiers(&mut self, node: Node) -> Result<Item, PassgenError> {
+        let (mut min, mut max) = (1, 1);
+        if self.peek() == Some('{') {
+            let start = self.pos;
+            let end = self.chars[start..]
+                .iter()
+                .position(|&c| c == '}')
+                .ok_or_else(|| self.error(start, "unclosed '{'"))?;
+            let body: String = self.chars[start + 1..start + end].iter().collect();
+            let count = |text: &str| {
+                text.trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&n| n <= MAX_REPEAT)
+                    .ok_or_else(|| {
+                        self.error(
+                            start,
+                            format!("invalid repetition count '{}' (0 to {})", text, MAX_REPEAT),
+                        )
+                    })
+            };
+            (min, max) = match body.split_once(',') {
+                Some((low, high)) => (count(low)?, count(high)?),
+                None => {
+                    let n = count(&body)?;
+                    (n, n)
+                }
+            };
+            if min > max {
+                return Err(self.error(start, format!("repetition {{{}}} has min > max", body)));
+            }
+            self.pos = start + end + 1;
+        }
+
+        let optional = self.peek() == Some('?');
+        if optional {
+            self.pos += 1;
+        }
+        let node_length = match &node {
+            Node::Literal(text) => text.chars().count(),
+            Node::Class(_) => 1,
+            Node::Word => self.word_length,
+            Node::Group(items) => items.iter().map(|item| item.max_length).sum(),
+        };
+        Ok(Item {
+            node,
+            min,
+            max,
+            optional,
+            max_length: node_length.saturating_mul(max),
+        })
+    }
+}
@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use zeroize::Zeroizing;
+
+use crate::PassgenError;
+
+/// Declarative password rules for a target system.
+///
+/// Policies are usually kept under version control as TOML or JSON files and
+/// loaded with [`PasswordPolicy::from_file`]; every field is optional.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: Option<usize>,
+    pub min_uppercase: usize,
+    pub min_lowercase: usize,
+    pub min_numbers: usize,
+    pub min_special: usize,
+    /// Characters the target system rejects
+    pub forbidden_chars: String,
+    /// Longest allowed run of the same character (e.g. 2 rejects "aaa")
+    pub max_repeated: Option<usize>,
+    /// Substrings that must not appear, compared case-insensitively
+    pub forbidden_substrings: Vec<String>,
+    pub required_prefix: Option<String>,
+    pub required_suffix: Option<String>,
+}
+
+/// A single rule of a [`PasswordPolicy`] that a password breaks.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum PolicyViolation {
+    TooShort {
+        length: usize,
+        min_length: usize,
+    },
+    TooLong {
+        length: usize,
+        max_length: usize,
+    },
+    TooFew {
+        class: String,
+        count: usize,
+        min: usize,
+    },
+    ForbiddenCharacter {
+        character: char,
+    },
+    RepeatedRun {
+        run: String,
+        max_repeated: usize,
+    },
+    ForbiddenSubstring {
+        substring: String,
+    },
+    MissingPrefix {
+        prefix: String,
+    },
+    MissingSuffix {
+        suffix: String,
+    },
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyViolation::TooShort { length, min_length } => write!(
+                f,
+                "length {} is shorter than the minimum of {}",
+                length, min_length
+            ),
+            PolicyViolation::TooLong { length, max_length } => write!(
+                f,
+                "length {} is longer than the maximum of {}",
+                length, max_length
+            ),
+            PolicyViolation::TooFew { class, count, min } => {
+                write!(
+                    f,
+                    "contains {} {} characters, needs at least {}",
+                    count, class, min
+                )
+            }
+            PolicyViolation::ForbiddenCharacter { character } => {
+                write!(f, "contains forbidden character '{}'", character)
+            }
+            PolicyViolation::RepeatedRun { run, max_repeated } => write!(
+                f,
+                "repeats '{}' more than {} times in a row",
+                run, max_repeated
+            ),
+            PolicyViolation::ForbiddenSubstring { substring } => {
+                write!(f, "contains forbidden substring '{}'", substring)
+            }
+            PolicyViolation::MissingPrefix { prefix } => {
+                write!(f, "does not start with '{}'", prefix)
+            }
+            PolicyViolation::MissingSuffix { suffix } => {
+                write!(f, "does not end with '{}'", suffix)
+            }
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Load a policy from a `.json` file, or a TOML file for any other extension
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, PassgenError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|err| PassgenError::Policy(format!("{}: {}", path.display(), err)))?;
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let parsed = if is_json {
+            Self::from_json_str(&text)
+        } else {
+            Self::from_toml_str(&text)
+        };
+        parsed.map_err(|err| match err {
+            PassgenError::Policy(reason) => {
+                PassgenError::Policy(format!("{}: {}", path.display(), reason))
+            }
+            other => other,
+        })
+    }
+
+    pub fn from_toml_str(text: &str) -> Result<Self, PassgenError> {
+        toml::from_str(text).map_err(|err| PassgenError::Policy(err.message().to_string()))
+    }
+
+    pub fn from_json_str(text: &str) -> Result<Self, PassgenError> {
+        serde_json::from_str(text).map_err(|err| PassgenError::Policy(err.to_string()))
+    }
+}
+
+/// Check a password against a policy, returning every rule it breaks
+pub fn check_policy(password: &str, policy: &PasswordPolicy) -> Vec<PolicyViolation> {
+    let mut violations = Vec::new();
+
+    let length = password.chars().count();
+    if length < policy.min_length {
+        violations.push(PolicyViolation::TooShort {
+            length,
+            min_length: policy.min_length,
+        });
+    }
+    if let Some(max_length) = policy.max_length.filter(|&max| length > max) {
+        violations.push(PolicyViolation::TooLong { length, max_length });
+    }
+
+    let chars = || password.chars();
+    let classes = [
+        (
+            "uppercase",
+            policy.min_uppercase,
+            chars().filter(char::is_ascii_uppercase).count(),
+        ),
+        (
+            "lowercase",
+            policy.min_lowercase,
+            chars().filter(char::is_ascii_lowercase).count(),
+        ),
+        (
+            "numbers",
+            policy.min_numbers,
+            chars().filter(char::is_ascii_digit).count(),
+        ),
+        (
+            "special",
+            policy.min_special,
+            chars().filter(|c| !c.is_alphanumeric()).count(),
+        ),
+    ];
+    for (class, min, count) in classes {
+        if count < min {
+            violations.push(PolicyViolation::TooFew {
+                class: class.to_string(),
+                count,
+                min,
+            });
+        }
+    }
+
+    let mut reported: Vec<char> = Vec::new();
+    for c in password.chars() {
+        if policy.forbidden_chars.contains(c) && !reported.contains(&c) {
+            reported.push(c);
+            violations.push(PolicyViolation::ForbiddenCharacter { character: c });
+        }
+    }
+
+    if let Some(max_repeated) = policy.max_repeated {
+        let mut chars = password.chars().peekable();
+        while let Some(c) = chars.next() {
+            let mut length = 1;
+            while chars.next_if_eq(&c).is_some() {
+                length += 1;
+            }
+            if length > max_repeated {
+                violations.push(PolicyViolation::RepeatedRun {
+                    run: std::iter::repeat_n(c, length).collect(),
+                    max_repeated,
+                });
+            }
+        }
+    }
+
+    let lowered = Zeroizing::new(password.to_lowercase());
+    for substring in &policy.forbidden_substrings {
+        if !substring.is_empty() && lowered.contains(&substring.to_lowercase()) {
+            violations.push(PolicyViolation::ForbiddenSubstring {
+                substring: substring.clone(),
+            });
+        }
+    }
+
+    if let Some(prefix) = policy
+        .required_prefix
+        .as_ref()
+        .filter(|p| !password.starts_with(p.as_str()))
+    {
+        violations.push(PolicyViolation::MissingPrefix {
+            prefix: prefix.clone(),
+        });
+    }
+    if let Some(suffix) = policy
+        .required_suffix
+        .as_ref()
+        .filter(|s| !password.ends_with(s.as_str()))
+    {
+        violations.push(PolicyViolation::MissingSuffix {
+            suffix: suffix.clone(),
+        });
+    }
+
+    violations
+}
@@ -0,0 +1,117 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::ops::Deref;
+use zeroize::Zeroize;
+
+/// A password that is wiped from memory when dropped and redacted from
+/// `Debug` output.
+///
+/// It derefs to `str` for reading; [`SecretString::expose_secret`] marks the
+/// places where the password leaves the type, e.g. to be printed. Growing it
+/// with [`SecretString::push_str`] wipes the old buffer when it moves, so no
+/// partial copies are left behind. Serializing writes the password in plain
+/// text, since that is what JSON output is for.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        SecretString(String::with_capacity(capacity))
+    }
+
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.reserve(c.len_utf8());
+        self.0.push(c);
+    }
+
+    pub fn push_str(&mut self, s: &str) {
+        self.reserve(s.len());
+        self.0.push_str(s);
+    }
+
+    /// Make room for `additional` bytes, moving to a new buffer by hand so
+    /// the old one can be wiped first
+    fn reserve(&mut self, additional: usize) {
+        if self.0.capacity() - self.0.len() >= additional {
+            return;
+        }
+        let capacity = (self.0.len() + additional).max(self.0.capacity() * 2);
+        let mut grown = String::with_capacity(capacity);
+        grown.push_str(&self.0);
+        self.0.zeroize();
+        self.0 = grown;
+    }
+
+    /// Collect `chars` and wipe them
+    pub(crate) fn from_chars(mut chars: Vec<char>) -> Self {
+        let mut secret = Self::with_capacity(chars.iter().map(|c| c.len_utf8()).sum());
+        chars.iter().for_each(|&c| secret.push(c));
+        chars.zeroize();
+        secret
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Deref for SecretString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+/// Takes ownership without copying
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        SecretString(secret)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(secret: &str) -> Self {
+        SecretString(secret.to_string())
+    }
+}
+
+impl FromIterator<char> for SecretString {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        let mut secret = SecretString::new();
+        iter.into_iter().for_each(|c| secret.push(c));
+        secret
+    }
+}
+
+impl Serialize for SecretString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(SecretString)
+    }
+}
@@ -0,0 +1,130 @@
+use passgen::{
+    AnalysisContext, BannedWords, PassgenError, PassphraseOptions, PasswordGenerator,
+    PasswordOptions, SmartPasswordMode, Wordlist, generate_password_with_rng, try_analyze_smart,
+    try_generate_password_with_rng, try_generate_smart_password_with_rng,
+};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+fn banned() -> BannedWords {
+    BannedWords::parse("# product names\nAcme\nroadrunner\n\n  springfield  \nacme\n")
+}
+
+#[test]
+fn parse_normalizes_and_skips_comments() {
+    assert_eq!(banned().words(), ["acme", "roadrunner", "springfield"]);
+    assert!(BannedWords::parse("# nothing\n\n").is_empty());
+}
+
+#[test]
+fn find_ignores_case_and_l33t_substitutions() {
+    let banned = banned();
+    assert_eq!(banned.find("xxACMExx"), Some("acme"));
+    assert_eq!(banned.find("4cm3"), Some("acme"));
+    assert_eq!(banned.find("R0adRunn3r!"), Some("roadrunner"));
+    assert_eq!(banned.find("spr1ngf!e1d"), Some("springfield"));
+    assert_eq!(banned.find("acne-road-runner"), None);
+}
+
+#[test]
+fn generators_never_emit_banned_words() {
+    let banned = BannedWords::new(["ab", "ba", "cab"]);
+    let opts = PasswordOptions {
+        length: 12,
+        lowercase: true,
+        exclude_chars: "defghijklmnopqrstuvwxyz".to_string(),
+        banned_words: Some(banned.clone()),
+        ..Default::default()
+    };
+    let mut rng = StdRng::seed_from_u64(7);
+    for _ in 0..20 {
+        let password = try_generate_password_with_rng(&opts, &mut rng).unwrap();
+        assert_eq!(banned.find(&password), None, "{}", password.expose_secret());
+        let password = generate_password_with_rng(&opts, &mut rng);
+        assert_eq!(banned.find(&password), None, "{}", password.expose_secret());
+    }
+
+    let pattern = SmartPasswordMode::Pattern("L{3}".to_string());
+    for _ in 0..20 {
+        let strength =
+            try_generate_smart_password_with_rng(pattern.clone(), &opts, &mut rng).unwrap();
+        assert_eq!(
+            banned.find(strength.password.as_ref().unwrap()),
+            None,
+            "{}",
+            strength.password.as_ref().unwrap().expose_secret()
+        );
+        assert_eq!(strength.banned_word, None);
+    }
+}
+
+#[test]
+fn unavoidable_banned_words_are_an_error() {
+    let opts = PasswordOptions {
+        length: 8,
+        numbers: true,
+        banned_words: Some(BannedWords::new([
+            "0", "1", "2", "3", "4", "5", "6", "7", "8", "9",
+        ])),
+        ..Default::default()
+    };
+    let result = try_generate_password_with_rng(&opts, &mut StdRng::seed_from_u64(1));
+    assert_eq!(
+        result,
+        Err(PassgenError::BannedWordsUnavoidable { attempts: 1000 })
+    );
+}
+
+#[test]
+fn analysis_penalizes_and_reports_banned_words() {
+    let password = "Roadrunner#Acme";
+    let plain = try_analyze_smart(password, &AnalysisContext::default()).unwrap();
+    let context = AnalysisContext {
+        banned_words: Some(banned()),
+        ..Default::default()
+    };
+    let checked = try_analyze_smart(password, &context).unwrap();
+    assert!(checked.strength_score < plain.strength_score);
+    assert_eq!(checked.banned_word.as_deref(), Some("acme"));
+    assert!(checked.warning.unwrap().contains("acme"));
+    assert_eq!(plain.banned_word, None);
+}
+
+#[test]
+fn passphrases_avoid_banned_words() {
+    let wordlist = Wordlist::parse("acme\nroad\nrunner\nspring\n").unwrap();
+    let phrase_opts = PassphraseOptions {
+        words: 3,
+        wordlist,
+        ..Default::default()
+    };
+    let opts = PasswordOptions {
+        banned_words: Some(BannedWords::new(["acme"])),
+        ..Default::default()
+    };
+    let generator = PasswordGenerator::smart_with_rng(
+        SmartPasswordMode::Passphrase(phrase_opts.clone()),
+        opts.clone(),
+        StdRng::seed_from_u64(3),
+    )
+    .unwrap();
+    for strength in generator.analyzed().take(20) {
+        let strength = strength.unwrap();
+        assert!(!strength.password.as_ref().unwrap().contains("acme"));
+        assert_eq!(strength.banned_word, None);
+    }
+
+    let only_acme = PassphraseOptions {
+        wordlist: Wordlist::parse("acme\nACME\n4cm3\n").unwrap(),
+        ..phrase_opts
+    };
+    assert_eq!(
+        try_generate_smart_password_with_rng(
+            SmartPasswordMode::Passphrase(only_acme),
+            &opts,
+            &mut StdRng::seed_from_u64(3),
+        )
+        .unwrap_err(),
+        PassgenError::BannedWordsUnavoidable { attempts: 1000 }
+    );
+}
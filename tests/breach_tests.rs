@@ -0,0 +1,80 @@
+use passgen::{BreachCheck, BreachCorpus, PassgenError, build_index, sha1};
+use std::fs;
+use std::path::PathBuf;
+
+fn hex(digest: &[u8; 20]) -> String {
+    digest.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+/// A sorted dump of "password0".."password{n-1}" seen i + 1 times each
+fn dump(n: usize, line_ending: &str) -> (Vec<(String, u64)>, String) {
+    let mut entries: Vec<(String, u64)> = (0..n)
+        .map(|i| (format!("password{}", i), i as u64 + 1))
+        .collect();
+    entries.sort_by_key(|(password, _)| sha1(password));
+    let text = entries
+        .iter()
+        .map(|(password, count)| format!("{}:{}{}", hex(&sha1(password)), count, line_ending))
+        .collect();
+    (entries, text)
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("passgen-{}-{}", std::process::id(), name))
+}
+
+#[test]
+fn text_dump_and_index_find_every_entry() {
+    let (entries, text) = dump(500, "\r\n");
+    let text_path = temp_path("dump.txt");
+    let index_path = temp_path("dump.bin");
+    fs::write(&text_path, &text).unwrap();
+    let records = build_index(text.as_bytes(), fs::File::create(&index_path).unwrap()).unwrap();
+    assert_eq!(records, 500);
+
+    for path in [&text_path, &index_path] {
+        let corpus = BreachCorpus::open(path).unwrap();
+        for (password, count) in &entries {
+            assert_eq!(corpus.count(password).unwrap(), *count, "{}", password);
+        }
+        for missing in ["password500", "correct horse battery staple", ""] {
+            assert_eq!(corpus.count(missing).unwrap(), 0);
+        }
+    }
+    fs::remove_file(text_path).unwrap();
+    fs::remove_file(index_path).unwrap();
+}
+
+#[test]
+fn tiny_and_hash_only_dumps() {
+    let path = temp_path("single.txt");
+    fs::write(&path, format!("{}\n", hex(&sha1("hunter2")))).unwrap();
+    let corpus = BreachCorpus::open(&path).unwrap();
+    assert_eq!(corpus.count("hunter2").unwrap(), 1);
+    assert_eq!(corpus.count("hunter3").unwrap(), 0);
+
+    fs::write(&path, "").unwrap();
+    assert_eq!(BreachCorpus::open(&path).unwrap().count("x").unwrap(), 0);
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn malformed_corpora_are_errors() {
+    let path = temp_path("bad.txt");
+    fs::write(&path, "not a hash\n").unwrap();
+    assert!(matches!(
+        BreachCorpus::open(&path),
+        Err(PassgenError::Breach(_))
+    ));
+    fs::remove_file(path).unwrap();
+    assert!(matches!(
+        BreachCorpus::open(temp_path("missing.txt")),
+        Err(PassgenError::Breach(_))
+    ));
+
+    let (_, text) = dump(10, "\n");
+    let mut lines: Vec<&str> = text.lines().collect();
+    lines.swap(3, 4);
+    let unsorted = lines.join("\n");
+    assert!(build_index(unsorted.as_bytes(), Vec::new()).is_err());
+}
@@ -0,0 +1,96 @@
+use std::process::{Command, Output};
+
+/// Run the binary with no config file in reach
+fn passgen(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_passgen"))
+        .args(args)
+        .env("HOME", env!("CARGO_TARGET_TMPDIR"))
+        .env_remove("PASSGEN_CONFIG")
+        .env_remove("XDG_CONFIG_HOME")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn seed_is_only_accepted_by_generators() {
+    for args in [
+        &["--seed", "7"][..],
+        &["generate", "--seed", "7"],
+        &["phrase", "--seed", "7"],
+        &["--passphrase", "--seed", "7"],
+    ] {
+        let first = passgen(args);
+        assert!(first.status.success(), "{:?}", first);
+        assert_eq!(first.stdout, passgen(args).stdout, "{:?}", args);
+    }
+
+    for args in [
+        &["analyze", "--seed", "7", "hunter2"][..],
+        &["check", "--seed", "7", "hunter2"],
+        &["breach-index", "--seed", "7", "in", "out"],
+    ] {
+        // Usage errors
+        assert_eq!(passgen(args).status.code(), Some(2), "{:?}", args);
+    }
+}
+
+#[test]
+fn phonetic_rejects_the_lowercase_flag() {
+    assert_eq!(passgen(&["--phonetic", "-w"]).status.code(), Some(2));
+    assert!(passgen(&["--phonetic", "-u", "-n"]).status.success());
+}
+
+#[test]
+fn config_flags_are_only_accepted_where_profiles_apply() {
+    for args in [
+        &["--profile", "nosuch"][..],
+        &["generate", "--profile", "nosuch"],
+        &["phrase", "--profile", "nosuch"],
+        &["analyze", "--profile", "nosuch", "hunter2"],
+    ] {
+        // No config file has the profile
+        assert_eq!(passgen(args).status.code(), Some(17), "{:?}", args);
+    }
+
+    for args in [
+        &["check", "--profile", "nosuch", "hunter2"][..],
+        &["check", "--config", "passgen.toml", "hunter2"],
+        &["breach-index", "--profile", "nosuch", "in", "out"],
+    ] {
+        assert_eq!(passgen(args).status.code(), Some(2), "{:?}", args);
+    }
+}
+
+#[test]
+fn options_the_profile_mode_ignores_are_rejected() {
+    let config = std::path::PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("cli-args-modes.toml");
+    std::fs::write(
+        &config,
+        "[profile.memorable]\nmode = \"phonetic\"\n\n\
+         [profile.key]\npattern = \"U{4}D{4}\"\n\n\
+         [profile.wifi]\nmode = \"passphrase\"\n",
+    )
+    .unwrap();
+    let config = config.to_str().unwrap();
+    let run = |args: &[&str]| passgen(&[&["--config", config][..], args].concat());
+
+    for (profile, flag) in [
+        ("memorable", &["--min-numbers", "2"][..]),
+        ("memorable", &["-w"]),
+        ("key", &["--charset", "xyz"]),
+        ("key", &["-l", "20"]),
+        ("wifi", &["--policy", "policy.toml"]),
+    ] {
+        let output = run(&[&["--profile", profile][..], flag].concat());
+        assert_eq!(output.status.code(), Some(2), "{} {:?}", profile, flag);
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("profile's"), "{}", stderr);
+    }
+
+    // A mode flag on the command line replaces the profile's mode
+    assert!(
+        run(&["--profile", "key", "--phonetic", "-u"])
+            .status
+            .success()
+    );
+}
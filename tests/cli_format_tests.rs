@@ -0,0 +1,179 @@
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Run the binary with no config file in reach
+fn passgen(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_passgen"))
+        .args(args)
+        .env("HOME", env!("CARGO_TARGET_TMPDIR"))
+        .env_remove("PASSGEN_CONFIG")
+        .env_remove("XDG_CONFIG_HOME")
+        .output()
+        .unwrap()
+}
+
+fn stdout(args: &[&str]) -> String {
+    let output = passgen(args);
+    assert!(output.status.success(), "{:?}", output);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn policy_file() -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("cli-format-policy.toml");
+    std::fs::write(&path, "min_length = 10\nmin_numbers = 1\n").unwrap();
+    path
+}
+
+const GENERATE: [&str; 6] = ["--seed", "1", "-l", "12", "-c", "3"];
+
+#[test]
+fn csv_has_a_header_and_a_row_per_password() {
+    let csv = stdout(&[&GENERATE[..], &["--format", "csv"]].concat());
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "password,entropy_bits,strength_score,strength_label,crack_time_seconds,\
+         crack_time_display,guesses_log10,generator_entropy_bits,character_sets,\
+         breach_count,banned_word,warning"
+    );
+    assert_eq!(lines.len(), 4);
+
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    for row in reader.records() {
+        let row = row.unwrap();
+        assert_eq!(row.len(), 12);
+        assert_eq!(row[0].chars().count(), 12);
+        assert_eq!(&row[8], "uppercase;lowercase;numbers;special");
+    }
+}
+
+#[test]
+fn null_output_is_bare_nul_terminated_passwords() {
+    let output = passgen(&[&GENERATE[..], &["-0"]].concat());
+    let passwords: Vec<&[u8]> = output.stdout.split(|&b| b == 0).collect();
+    assert_eq!(passwords.len(), 4);
+    assert!(passwords[..3].iter().all(|p| p.len() == 12));
+    assert!(passwords[3].is_empty());
+    assert!(!output.stdout.contains(&b'\n'));
+}
+
+#[test]
+fn bare_output_skips_analysis_but_not_passwords() {
+    let bare = passgen(&[&GENERATE[..], &["-0"]].concat()).stdout;
+    let ndjson = stdout(&[&GENERATE[..], &["--format", "ndjson"]].concat());
+    let analyzed: Vec<String> = ndjson
+        .lines()
+        .map(|line| {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            format!("{}\0", value["password"].as_str().unwrap())
+        })
+        .collect();
+    assert_eq!(String::from_utf8(bare).unwrap(), analyzed.concat());
+
+    let lone = stdout(&["--seed", "1", "-l", "12"]);
+    assert_eq!(lone, analyzed[0].replace('\0', "\n"));
+}
+
+#[test]
+fn only_omitted_passwords_are_shown_as_omitted() {
+    let generated = stdout(&[
+        "generate",
+        "--pattern",
+        "D?",
+        "--seed",
+        "1",
+        "-c",
+        "2",
+        "-t",
+    ]);
+    assert!(generated.starts_with("Password 1: \n"), "{}", generated);
+    assert!(!generated.contains("(omitted)"));
+
+    let analyzed = stdout(&["analyze", "--omit-password", "abc"]);
+    assert!(analyzed.starts_with("(omitted)\n"), "{}", analyzed);
+}
+
+#[test]
+fn table_columns_line_up() {
+    let table = stdout(&[&GENERATE[..], &["--format", "table"]].concat());
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert!(lines[1].chars().all(|c| c == '-' || c == ' '));
+
+    // Every column starts where its header does
+    let header = lines[0];
+    let starts: Vec<usize> = ["Password", "Entropy", "Score", "Strength", "Crack time"]
+        .iter()
+        .map(|title| header.find(title).unwrap())
+        .collect();
+    for line in &lines[1..] {
+        for &start in &starts {
+            assert_eq!(&line[start - 2..start], "  ", "{}", line);
+            assert_ne!(&line[start..start + 1], " ", "{}", line);
+        }
+    }
+}
+
+#[test]
+fn yaml_and_toml_hold_the_whole_analysis() {
+    let yaml: serde_yaml::Value =
+        serde_yaml::from_str(&stdout(&[&GENERATE[..], &["--format", "yaml"]].concat())).unwrap();
+    let toml: toml::Value =
+        toml::from_str(&stdout(&[&GENERATE[..], &["--format", "toml"]].concat())).unwrap();
+
+    let passwords = yaml["passwords"].as_sequence().unwrap();
+    assert_eq!(passwords.len(), 3);
+    assert_eq!(passwords[0]["password"].as_str().unwrap().len(), 12);
+    assert!(yaml["average_entropy"].as_f64().unwrap() > 70.0);
+
+    let passwords = toml["passwords"].as_array().unwrap();
+    assert_eq!(passwords.len(), 3);
+    assert_eq!(passwords[0]["password"].as_str().unwrap().len(), 12);
+    assert_eq!(
+        toml["passwords"][0]["password"],
+        toml::Value::from(yaml["passwords"][0]["password"].as_str().unwrap())
+    );
+}
+
+#[test]
+fn check_supports_every_format() {
+    let policy = policy_file();
+    let check = |format: &str| {
+        let output = passgen(&[
+            "check",
+            "--policy",
+            policy.to_str().unwrap(),
+            "--format",
+            format,
+            "short",
+            "longenough12",
+        ]);
+        // One password breaks the policy
+        assert_eq!(output.status.code(), Some(14), "{:?}", output);
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let ndjson = check("ndjson");
+    let results: Vec<serde_json::Value> = ndjson
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(results[0]["compliant"], false);
+    assert_eq!(results[0]["violations"][0]["rule"], "too_short");
+    assert_eq!(results[1]["compliant"], true);
+
+    let csv = check("csv");
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "password,compliant,violations");
+    assert!(lines[1].starts_with("short,false,\"length 5 is shorter"));
+    assert_eq!(lines[2], "longenough12,true,");
+
+    assert_eq!(check("null"), "longenough12\0");
+
+    let table = check("table");
+    assert!(table.starts_with("#  Password      Compliant  Violations\n"));
+
+    for format in ["text", "json", "yaml", "toml"] {
+        assert!(check(format).contains("longenough12"), "{}", format);
+    }
+}
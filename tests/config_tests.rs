@@ -0,0 +1,115 @@
+use passgen::{AttackerModel, Capitalization, Config, Mode, OutputFormat, PassgenError};
+use std::path::{Path, PathBuf};
+
+const CONFIG: &str = r#"
+length = 24
+uppercase = true
+numbers = true
+
+[profile.wifi]
+mode = "passphrase"
+words = 5
+capitalize = "title"
+numbers = false
+
+[profile.key]
+pattern = "[A-Z0-9]{4}('-'[A-Z0-9]{4}){3}"
+format = "json"
+attackers = ["offline_slow_hash", "hsm=50"]
+banned_words = "banned.txt"
+"#;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("passgen-{}-{}", std::process::id(), name))
+}
+
+#[test]
+fn profiles_override_top_level_defaults() {
+    let config = Config::from_toml_str(CONFIG).unwrap();
+    assert_eq!(config.profile_names().collect::<Vec<_>>(), ["key", "wifi"]);
+
+    let defaults = config.profile(None).unwrap();
+    assert_eq!(defaults.length, Some(24));
+    assert_eq!(defaults.numbers, Some(true));
+    assert_eq!(defaults.mode(), None);
+
+    let wifi = config.profile(Some("wifi")).unwrap();
+    assert_eq!(wifi.length, Some(24));
+    assert_eq!(wifi.uppercase, Some(true));
+    assert_eq!(wifi.numbers, Some(false));
+    assert_eq!(wifi.mode(), Some(Mode::Passphrase));
+    assert_eq!(wifi.words, Some(5));
+    assert_eq!(wifi.capitalize, Some(Capitalization::Title));
+
+    let key = config.profile(Some("key")).unwrap();
+    assert_eq!(key.mode(), Some(Mode::Pattern));
+    assert_eq!(key.format, Some(OutputFormat::Json));
+    assert_eq!(
+        key.attackers.unwrap(),
+        [
+            AttackerModel::builtin("offline_slow_hash").unwrap(),
+            AttackerModel::new("hsm", 50.0)
+        ]
+    );
+}
+
+#[test]
+fn unknown_profiles_and_keys_are_errors() {
+    let config = Config::from_toml_str(CONFIG).unwrap();
+    match config.profile(Some("aws")) {
+        Err(PassgenError::Config(reason)) => assert!(reason.contains("key, wifi"), "{}", reason),
+        other => panic!("expected a config error, got {:?}", other),
+    }
+
+    for text in [
+        "lenght = 24",
+        "[profile.x]\ncapitalize = \"shout\"",
+        "[profile.x]\nattackers = [\"hsm=fast\"]",
+        "mode = \"pattern\"",
+        "format = \"xml\"",
+    ] {
+        assert!(
+            matches!(Config::from_toml_str(text), Err(PassgenError::Config(_))),
+            "{}",
+            text
+        );
+    }
+}
+
+#[test]
+fn paths_are_relative_to_the_config_file() {
+    let dir = temp_path("config");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("config.toml");
+    std::fs::write(&path, CONFIG).unwrap();
+
+    let key = Config::from_file(&path)
+        .unwrap()
+        .profile(Some("key"))
+        .unwrap();
+    assert_eq!(
+        key.banned_words.as_deref(),
+        Some(dir.join("banned.txt").as_path())
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let inline = Config::from_toml_str(CONFIG)
+        .unwrap()
+        .profile(Some("key"))
+        .unwrap();
+    assert_eq!(
+        inline.banned_words.as_deref(),
+        Some(Path::new("banned.txt"))
+    );
+}
+
+#[test]
+fn output_formats_parse_by_name() {
+    for format in OutputFormat::ALL {
+        assert_eq!(format.to_string().parse(), Ok(format));
+    }
+    assert_eq!("NDJSON".parse(), Ok(OutputFormat::Ndjson));
+    assert!("xml".parse::<OutputFormat>().is_err());
+    assert!(OutputFormat::Csv.is_streamed());
+    assert!(!OutputFormat::Toml.is_streamed());
+}
@@ -0,0 +1,79 @@
+use passgen::{
+    DurationLocale, HumanDuration, PasswordOptions, TimeUnit, format_duration,
+    format_duration_with, try_analyze_password_strength,
+};
+
+const YEAR: f64 = 365.0 * 24.0 * 3600.0;
+
+fn years(years: f64) -> String {
+    format_duration((years * YEAR).log10())
+}
+
+#[test]
+fn short_durations_use_small_units() {
+    assert_eq!(format_duration(-0.5), "less than a second");
+    assert_eq!(format_duration(f64::NEG_INFINITY), "less than a second");
+    assert_eq!(format_duration(0.0), "1 second");
+    assert_eq!(format_duration(59.9f64.log10()), "59 seconds");
+    assert_eq!(format_duration(60f64.log10()), "1 minute");
+    assert_eq!(format_duration((3.0 * 86400.0f64).log10()), "3 days");
+    assert_eq!(format_duration((45.0 * 86400.0f64).log10()), "1 month");
+}
+
+#[test]
+fn long_durations_use_centuries_millennia_and_beyond() {
+    assert_eq!(years(42.0), "42 years");
+    assert_eq!(years(250.0), "2 centuries");
+    assert_eq!(years(1_500.0), "1 millennium");
+    assert_eq!(years(64_000.0), "64 millennia");
+    assert_eq!(years(3.2e7), "32 million years");
+    assert_eq!(years(5e9), "5 billion years");
+    assert_eq!(
+        years(2e13),
+        "longer than the age of the universe (10^13 years)"
+    );
+    assert_eq!(years(1e25), "effectively forever");
+    assert_eq!(format_duration(f64::INFINITY), "effectively forever");
+}
+
+#[test]
+fn huge_entropy_never_overflows_to_infinity() {
+    let opts = PasswordOptions {
+        length: 1000,
+        lowercase: true,
+        ..Default::default()
+    };
+    let strength = try_analyze_password_strength(&"a".repeat(1000), &opts).unwrap();
+    assert!(strength.crack_time_seconds.is_finite());
+    assert!(strength.crack_time_log10 > 1000.0);
+    assert_eq!(strength.crack_time_display, "effectively forever");
+    assert!(
+        serde_json::to_string(&strength)
+            .unwrap()
+            .contains("\"crack_time_log10\":")
+    );
+}
+
+struct German;
+
+impl DurationLocale for German {
+    fn render(&self, duration: &HumanDuration) -> String {
+        match duration {
+            HumanDuration::Units {
+                count,
+                unit: TimeUnit::Day,
+            } => format!("{} Tage", count),
+            HumanDuration::Forever => "praktisch ewig".to_string(),
+            _ => "?".to_string(),
+        }
+    }
+}
+
+#[test]
+fn locales_render_structured_durations() {
+    assert_eq!(
+        format_duration_with((3.0 * 86400.0f64).log10(), &German),
+        "3 Tage"
+    );
+    assert_eq!(format_duration_with(1e6, &German), "praktisch ewig");
+}
@@ -0,0 +1,117 @@
+use passgen::{
+    AnalysisSummary, PassgenError, PassphraseOptions, PasswordAnalysis, PasswordGenerator,
+    PasswordOptions, SecretString, SmartPasswordMode, generate_multiple_passwords,
+    try_generate_password_with_rng,
+};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+fn opts() -> PasswordOptions {
+    PasswordOptions {
+        length: 20,
+        uppercase: true,
+        lowercase: true,
+        numbers: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn generator_matches_single_password_calls() {
+    let generated: Vec<SecretString> =
+        PasswordGenerator::with_rng(opts(), StdRng::seed_from_u64(9))
+            .unwrap()
+            .take(5)
+            .collect();
+
+    let mut rng = StdRng::seed_from_u64(9);
+    let expected: Vec<SecretString> = (0..5)
+        .map(|_| try_generate_password_with_rng(&opts(), &mut rng).unwrap())
+        .collect();
+    assert_eq!(generated, expected);
+}
+
+#[test]
+fn analyzed_generator_covers_every_mode() {
+    let modes = [
+        SmartPasswordMode::Phonetic,
+        SmartPasswordMode::Pattern("U{3}'-'D{4}".to_string()),
+        SmartPasswordMode::Passphrase(PassphraseOptions::default()),
+    ];
+    for mode in modes {
+        let generator =
+            PasswordGenerator::smart_with_rng(mode, opts(), StdRng::seed_from_u64(1)).unwrap();
+        for strength in generator.analyzed().take(3) {
+            let strength = strength.unwrap();
+            assert!(!strength.password.as_ref().unwrap().is_empty());
+            assert!(strength.generator_entropy_bits.unwrap() > 0.0);
+        }
+    }
+}
+
+#[test]
+fn bad_options_fail_before_generating() {
+    let no_sets = PasswordOptions {
+        length: 8,
+        ..Default::default()
+    };
+    assert!(matches!(
+        PasswordGenerator::new(no_sets),
+        Err(PassgenError::NoCharacterSets)
+    ));
+    assert!(matches!(
+        PasswordGenerator::smart(SmartPasswordMode::Pattern("U{".to_string()), opts()),
+        Err(PassgenError::InvalidPattern { .. })
+    ));
+    assert!(matches!(
+        PasswordGenerator::smart(SmartPasswordMode::Pattern("'abc'".to_string()), opts()),
+        Err(PassgenError::EmptyPattern)
+    ));
+    let empty = PasswordOptions {
+        length: 0,
+        ..opts()
+    };
+    assert!(matches!(
+        PasswordGenerator::smart(SmartPasswordMode::Phonetic, empty),
+        Err(PassgenError::ZeroLength)
+    ));
+    let no_words = PassphraseOptions {
+        words: 0,
+        ..Default::default()
+    };
+    assert!(matches!(
+        PasswordGenerator::smart(SmartPasswordMode::Passphrase(no_words), opts()),
+        Err(PassgenError::NoWords)
+    ));
+}
+
+#[test]
+fn running_summary_matches_batch_averages() {
+    let passwords: Vec<_> = PasswordGenerator::with_rng(opts(), StdRng::seed_from_u64(4))
+        .unwrap()
+        .analyzed()
+        .take(50)
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    let mut summary = AnalysisSummary::default();
+    passwords.iter().for_each(|p| summary.add(p));
+    let total: f64 = passwords.iter().map(|p| p.entropy_bits).sum();
+    assert_eq!(summary.count, 50);
+    assert!((summary.average_entropy - total / 50.0).abs() < 1e-9);
+
+    let analysis = PasswordAnalysis::from_passwords(passwords);
+    assert_eq!(
+        analysis.average_strength_score,
+        summary.average_strength_score
+    );
+}
+
+#[test]
+fn empty_batches_average_to_zero() {
+    let analysis = generate_multiple_passwords(&opts(), 0);
+    assert_eq!(analysis.count, 0);
+    assert_eq!(analysis.average_entropy, 0.0);
+    assert_eq!(analysis.average_strength_score, 0.0);
+    assert_eq!(AnalysisSummary::default().average_entropy, 0.0);
+}
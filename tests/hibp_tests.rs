@@ -0,0 +1,85 @@
+#![cfg(feature = "hibp")]
+
+use passgen::{BreachCheck, RangeClient, sha1};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+
+fn hex(digest: &[u8; 20]) -> String {
+    digest.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+/// Serve `responses` in order and report each request's path and padding header
+fn mock_server(responses: Vec<String>) -> (String, mpsc::Receiver<(String, bool)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/range", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for body in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut padding = false;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                padding |= header.to_ascii_lowercase().starts_with("add-padding: true");
+            }
+            let path = request_line.split_whitespace().nth(1).unwrap().to_string();
+            tx.send((path, padding)).unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+    });
+    (url, rx)
+}
+
+#[test]
+fn only_the_prefix_is_sent_and_suffixes_match_locally() {
+    let hash = hex(&sha1("password"));
+    let (prefix, suffix) = hash.split_at(5);
+    let body = format!(
+        "0018A45C4D1DEF81644B54AB7F969B88D65:1\r\n{}:9545824\r\n{}:0\r\n",
+        suffix,
+        &hex(&sha1("decoy"))[5..]
+    );
+    let (url, requests) = mock_server(vec![body.clone(), body]);
+
+    let client = RangeClient::with_base_url(url);
+    assert_eq!(client.count("password").unwrap(), 9545824);
+    assert_eq!(
+        requests.recv().unwrap(),
+        (format!("/range/{}", prefix), true)
+    );
+
+    // Padding entries carry a count of 0 and never match a real password
+    let unpadded = client.padding(false);
+    assert_eq!(unpadded.count_sha1(&sha1("password")).unwrap(), 9545824);
+    assert!(!requests.recv().unwrap().1);
+}
+
+#[test]
+fn missing_suffix_and_server_errors() {
+    let (url, _requests) = mock_server(vec![
+        "0018A45C4D1DEF81644B54AB7F969B88D65:3\r\n".to_string(),
+    ]);
+    assert_eq!(
+        RangeClient::with_base_url(url.clone())
+            .count("hunter2")
+            .unwrap(),
+        0
+    );
+
+    // The mock server is gone, so the next request fails to connect
+    assert!(RangeClient::with_base_url(url).count("hunter2").is_err());
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "parallel")]
+
+use passgen::{
+    AnalysisContext, BannedWords, Parallelism, PasswordGenerator, PasswordOptions, SecretString,
+    SmartPasswordMode, analyze_parallel, try_analyze_smart,
+};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::collections::HashSet;
+
+fn opts() -> PasswordOptions {
+    PasswordOptions {
+        length: 20,
+        uppercase: true,
+        lowercase: true,
+        numbers: true,
+        ..Default::default()
+    }
+}
+
+fn passwords(jobs: usize, ordered: bool, count: usize) -> Vec<SecretString> {
+    PasswordGenerator::with_rng(opts(), StdRng::seed_from_u64(5))
+        .unwrap()
+        .parallel(Parallelism { jobs, ordered })
+        .take(count)
+        .map(|result| result.unwrap().password.unwrap())
+        .collect()
+}
+
+#[test]
+fn ordered_output_does_not_depend_on_thread_count() {
+    let single = passwords(1, true, 300);
+    assert_eq!(passwords(4, true, 300), single);
+    assert_eq!(passwords(0, true, 300), single);
+    assert!(single.iter().all(|p| p.len() == 20));
+}
+
+#[test]
+fn unordered_output_is_analyzed_and_distinct() {
+    let generated = passwords(3, false, 200);
+    assert_eq!(generated.len(), 200);
+    let distinct: HashSet<_> = generated.iter().collect();
+    assert_eq!(distinct.len(), 200);
+}
+
+#[test]
+fn smart_modes_run_in_parallel() {
+    let generator = PasswordGenerator::smart_with_rng(
+        SmartPasswordMode::Pattern("U{3}'-'D{4}".to_string()),
+        opts(),
+        StdRng::seed_from_u64(1),
+    )
+    .unwrap();
+    let parallelism = Parallelism {
+        jobs: 2,
+        ordered: false,
+    };
+    for result in generator.parallel(parallelism).take(50) {
+        let strength = result.unwrap();
+        assert_eq!(strength.password.as_ref().unwrap().len(), 8);
+        assert!(strength.generator_entropy_bits.is_some());
+    }
+}
+
+#[test]
+fn ordered_analysis_matches_sequential() {
+    let input: Vec<SecretString> = (0..500)
+        .map(|i| SecretString::from(format!("Summer{}!acme", i)))
+        .collect();
+    let context = AnalysisContext {
+        user_inputs: vec!["acme".to_string()],
+        banned_words: Some(BannedWords::new(["summer"])),
+    };
+
+    let expected: Vec<_> = input
+        .iter()
+        .map(|p| try_analyze_smart(p, &context))
+        .map(|result| serde_json::to_value(result.unwrap()).unwrap())
+        .collect();
+    let parallelism = Parallelism {
+        jobs: 4,
+        ordered: true,
+    };
+    let actual: Vec<_> = analyze_parallel(input, context, parallelism)
+        .map(|result| serde_json::to_value(result.unwrap()).unwrap())
+        .collect();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn unique_generators_stay_unique_in_parallel() {
+    let opts = PasswordOptions {
+        length: 3,
+        numbers: true,
+        ..Default::default()
+    };
+    let parallelism = Parallelism {
+        jobs: 3,
+        ordered: false,
+    };
+    let generated: HashSet<SecretString> = PasswordGenerator::new(opts)
+        .unwrap()
+        .unique(1000)
+        .unwrap()
+        .parallel(parallelism)
+        .take(1000)
+        .map(|result| result.unwrap().password.unwrap())
+        .collect();
+    assert_eq!(generated.len(), 1000);
+}
@@ -1,4 +1,16 @@
-use passgen::{generate_password, generate_password_with_strength, generate_multiple_passwords, PasswordOptions, calculate_entropy, get_charset_size};
+use passgen::{
+    AnalysisContext, AttackerModel, LOWERCASE, MatchKind, NUMBERS, PassgenError, PasswordOptions,
+    SPECIAL, SmartPasswordMode, UPPERCASE, calculate_constrained_entropy, calculate_entropy,
+    generate_multiple_passwords, generate_password, generate_password_with_rng,
+    generate_password_with_strength, generate_pattern_password, generate_pattern_password_with_rng,
+    generate_phonetic_password, generate_phonetic_password_with_rng, get_character_sets,
+    get_charset_size, pattern_entropy, phonetic_entropy, try_analyze_password_strength,
+    try_analyze_smart, try_generate_multiple_passwords_with_rng, try_generate_password,
+    try_generate_password_with_strength, try_generate_pattern_password,
+    try_generate_phonetic_password, try_generate_smart_password,
+};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 
 fn has_uppercase(s: &str) -> bool {
     s.chars().any(|c| c.is_ascii_uppercase())
@@ -23,6 +35,7 @@ fn generates_requested_length_uppercase_only() {
         numbers: false,
         special: false,
         avoid_ambiguous: false,
+        ..Default::default()
     };
     let pwd = generate_password(&opts);
     assert_eq!(pwd.len(), 24);
@@ -38,6 +51,7 @@ fn includes_each_selected_type_when_length_sufficient() {
         numbers: true,
         special: true,
         avoid_ambiguous: false,
+        ..Default::default()
     };
     let pwd = generate_password(&opts);
     assert_eq!(pwd.len(), 32);
@@ -56,6 +70,7 @@ fn avoid_ambiguous_filters_characters() {
         numbers: true,
         special: false,
         avoid_ambiguous: true,
+        ..Default::default()
     };
     let pwd = generate_password(&opts);
     // none of these should appear when avoid_ambiguous is set
@@ -73,6 +88,7 @@ fn only_special_characters_when_selected() {
         numbers: false,
         special: true,
         avoid_ambiguous: false,
+        ..Default::default()
     };
     let pwd = generate_password(&opts);
     let specials = "!@#$%^&*()-=_+[]{}|;:,.<>?";
@@ -89,13 +105,14 @@ fn zero_length_is_allowed_and_empty() {
         numbers: true,
         special: true,
         avoid_ambiguous: false,
+        ..Default::default()
     };
     let pwd = generate_password(&opts);
     assert_eq!(pwd.len(), 0);
 }
 
 #[test]
-fn no_sets_selected_returns_default_password() {
+fn no_sets_selected_is_an_error() {
     let opts = PasswordOptions {
         length: 16,
         uppercase: false,
@@ -103,9 +120,64 @@ fn no_sets_selected_returns_default_password() {
         numbers: false,
         special: false,
         avoid_ambiguous: false,
+        ..Default::default()
     };
-    let pwd = generate_password(&opts);
-    assert_eq!(pwd, "DefaultPwd1!");
+    assert_eq!(try_generate_password(&opts), Err(PassgenError::NoCharacterSets));
+}
+
+#[test]
+#[should_panic]
+fn no_sets_selected_panics_in_infallible_api() {
+    let opts = PasswordOptions {
+        length: 16,
+        ..Default::default()
+    };
+    generate_password(&opts);
+}
+
+#[test]
+#[should_panic(expected = "minimum")]
+fn unsatisfiable_minimums_panic_in_infallible_api() {
+    let opts = PasswordOptions {
+        length: 6,
+        uppercase: true,
+        min_uppercase: 4,
+        min_numbers: 3,
+        ..Default::default()
+    };
+    generate_password(&opts);
+}
+
+#[test]
+fn length_shorter_than_sets_is_an_error() {
+    let opts = PasswordOptions {
+        length: 2,
+        uppercase: true,
+        lowercase: true,
+        numbers: true,
+        special: true,
+        avoid_ambiguous: false,
+        ..Default::default()
+    };
+    assert_eq!(
+        try_generate_password(&opts),
+        Err(PassgenError::LengthTooShort { length: 2, sets: 4 })
+    );
+}
+
+#[test]
+fn empty_pattern_and_password_are_errors() {
+    let opts = PasswordOptions::default();
+    assert_eq!(try_generate_pattern_password("", &opts), Err(PassgenError::EmptyPattern));
+    assert_eq!(try_generate_pattern_password("'xyz'", &opts), Err(PassgenError::EmptyPattern));
+    assert!(matches!(
+        try_generate_pattern_password("xyz", &opts),
+        Err(PassgenError::InvalidPattern { position: 0, .. })
+    ));
+    assert!(matches!(
+        try_analyze_smart("", &AnalysisContext::default()),
+        Err(PassgenError::EmptyPassword)
+    ));
 }
 
 #[test]
@@ -117,6 +189,7 @@ fn short_length_still_within_selected_union() {
         numbers: true,
         special: true,
         avoid_ambiguous: false,
+        ..Default::default()
     };
     let pwd = generate_password(&opts);
     assert_eq!(pwd.len(), 2);
@@ -143,6 +216,7 @@ fn get_charset_size_with_all_options() {
         numbers: true,
         special: true,
         avoid_ambiguous: false,
+        ..Default::default()
     };
     let size = get_charset_size(&opts);
     // 26 uppercase + 26 lowercase + 10 numbers + 26 special = 88
@@ -158,6 +232,7 @@ fn get_charset_size_with_avoid_ambiguous() {
         numbers: true,
         special: false,
         avoid_ambiguous: true,
+        ..Default::default()
     };
     let size = get_charset_size(&opts);
     // 24 uppercase (I,O removed) + 25 lowercase (l removed) + 8 numbers (0,1 removed) = 57
@@ -173,10 +248,11 @@ fn generate_password_with_strength_returns_valid_data() {
         numbers: true,
         special: true,
         avoid_ambiguous: false,
+        ..Default::default()
     };
     let pwd_strength = generate_password_with_strength(&opts);
 
-    assert_eq!(pwd_strength.password.len(), 20);
+    assert_eq!(pwd_strength.password.as_ref().unwrap().len(), 20);
     assert!(pwd_strength.entropy_bits > 0.0);
     assert!(pwd_strength.strength_score <= 4);
     assert!(!pwd_strength.strength_label.is_empty());
@@ -196,6 +272,7 @@ fn generate_multiple_passwords_correct_count() {
         numbers: false,
         special: false,
         avoid_ambiguous: false,
+        ..Default::default()
     };
     let analysis = generate_multiple_passwords(&opts, 5);
 
@@ -206,7 +283,7 @@ fn generate_multiple_passwords_correct_count() {
 
     // Check that all passwords have the correct length and only use expected character sets
     for pwd_strength in &analysis.passwords {
-        assert_eq!(pwd_strength.password.len(), 12);
+        assert_eq!(pwd_strength.password.as_ref().unwrap().len(), 12);
         assert!(pwd_strength.character_sets.contains(&"uppercase".to_string()));
         assert!(pwd_strength.character_sets.contains(&"lowercase".to_string()));
         assert!(!pwd_strength.character_sets.contains(&"numbers".to_string()));
@@ -223,10 +300,11 @@ fn strength_analysis_for_weak_password() {
         numbers: false,
         special: false,
         avoid_ambiguous: false,
+        ..Default::default()
     };
     let pwd_strength = generate_password_with_strength(&opts);
 
-    assert_eq!(pwd_strength.password.len(), 4);
+    assert_eq!(pwd_strength.password.as_ref().unwrap().len(), 4);
     assert!(pwd_strength.entropy_bits < 30.0); // Should be quite low entropy
     assert!(pwd_strength.strength_score <= 2); // Should be weak or very weak
 }
@@ -240,10 +318,11 @@ fn strength_analysis_for_strong_password() {
         numbers: true,
         special: true,
         avoid_ambiguous: true,
+        ..Default::default()
     };
     let pwd_strength = generate_password_with_strength(&opts);
 
-    assert_eq!(pwd_strength.password.len(), 32);
+    assert_eq!(pwd_strength.password.as_ref().unwrap().len(), 32);
     assert!(pwd_strength.entropy_bits > 100.0); // Should be high entropy
     assert!(pwd_strength.strength_score >= 3); // Should be strong or very strong
 }
@@ -257,6 +336,7 @@ fn json_serialization_works() {
         numbers: true,
         special: true,
         avoid_ambiguous: false,
+        ..Default::default()
     };
     let analysis = generate_multiple_passwords(&opts, 2);
 
@@ -267,5 +347,419 @@ fn json_serialization_works() {
     assert!(json.contains("strength_score"));
 }
 
+#[test]
+fn custom_symbol_set_replaces_default_specials() {
+    let opts = PasswordOptions {
+        length: 40,
+        special: true,
+        symbols: Some("!#-".to_string()),
+        ..Default::default()
+    };
+    let pwd = generate_password(&opts);
+    assert!(pwd.chars().all(|c| "!#-".contains(c)));
+    assert_eq!(get_charset_size(&opts), 3);
+}
+
+#[test]
+fn custom_sets_are_included_and_deduplicated_in_pool() {
+    let opts = PasswordOptions {
+        length: 24,
+        numbers: true,
+        custom_sets: vec!["ABCDEF".to_string(), "0123".to_string()],
+        ..Default::default()
+    };
+    let pwd = generate_password(&opts);
+    assert!(pwd.chars().any(|c| "ABCDEF".contains(c)));
+    assert!(pwd.chars().all(|c| c.is_ascii_digit() || "ABCDEF".contains(c)));
+    // 10 digits + 6 letters; "0123" overlaps with the digits
+    assert_eq!(get_charset_size(&opts), 16);
+    assert_eq!(get_character_sets(&opts).len(), 3);
+}
+
+#[test]
+fn excluded_characters_never_appear() {
+    let opts = PasswordOptions {
+        length: 64,
+        uppercase: true,
+        lowercase: true,
+        numbers: true,
+        special: true,
+        exclude_chars: "<>|;aeiou".to_string(),
+        ..Default::default()
+    };
+    let pwd_strength = try_generate_password_with_strength(&opts).unwrap();
+    for bad in "<>|;aeiou".chars() {
+        assert!(!pwd_strength.password.as_ref().unwrap().contains(bad));
+    }
+    assert_eq!(get_charset_size(&opts), 79);
+    let expected = calculate_constrained_entropy(&opts, 64).unwrap();
+    assert!((pwd_strength.entropy_bits - expected).abs() < 0.001);
+}
+
+fn count(s: &str, set: &str) -> usize {
+    s.chars().filter(|c| set.contains(*c)).count()
+}
+
+#[test]
+fn minimum_counts_are_met() {
+    let opts = PasswordOptions {
+        length: 12,
+        uppercase: true,
+        lowercase: true,
+        min_uppercase: 3,
+        min_numbers: 2,
+        min_special: 2,
+        ..Default::default()
+    };
+    for _ in 0..20 {
+        let pwd = try_generate_password(&opts).unwrap();
+        assert_eq!(pwd.chars().count(), 12);
+        assert!(count(&pwd, UPPERCASE) >= 3);
+        assert!(count(&pwd, NUMBERS) >= 2);
+        assert!(count(&pwd, SPECIAL) >= 2);
+        assert!(count(&pwd, LOWERCASE) >= 1);
+    }
+}
+
+#[test]
+fn maximum_counts_are_respected() {
+    let opts = PasswordOptions {
+        length: 20,
+        uppercase: true,
+        lowercase: true,
+        numbers: true,
+        special: true,
+        max_special: Some(1),
+        max_numbers: Some(2),
+        ..Default::default()
+    };
+    for _ in 0..20 {
+        let pwd = try_generate_password(&opts).unwrap();
+        assert_eq!(pwd.chars().count(), 20);
+        assert_eq!(count(&pwd, SPECIAL), 1);
+        assert!(count(&pwd, NUMBERS) <= 2);
+    }
+}
+
+#[test]
+fn impossible_constraints_are_errors() {
+    let base = PasswordOptions {
+        length: 6,
+        uppercase: true,
+        numbers: true,
+        ..Default::default()
+    };
+    let too_many = PasswordOptions {
+        min_uppercase: 4,
+        min_numbers: 3,
+        ..base.clone()
+    };
+    assert_eq!(
+        try_generate_password(&too_many),
+        Err(PassgenError::MinimumsExceedLength { minimum: 7, length: 6 })
+    );
+    let too_few = PasswordOptions {
+        max_uppercase: Some(2),
+        max_numbers: Some(2),
+        ..base.clone()
+    };
+    assert_eq!(
+        try_generate_password(&too_few),
+        Err(PassgenError::MaximumsBelowLength { maximum: 4, length: 6 })
+    );
+    // Every character of the custom set is a capped lowercase letter
+    let capped_custom = PasswordOptions {
+        length: 8,
+        uppercase: true,
+        lowercase: true,
+        max_uppercase: Some(2),
+        max_lowercase: Some(2),
+        custom_sets: vec!["ab".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(
+        try_generate_password(&capped_custom),
+        Err(PassgenError::MaximumsBelowLength { maximum: 4, length: 8 })
+    );
+    let crossed = PasswordOptions {
+        min_numbers: 3,
+        max_numbers: Some(2),
+        ..base
+    };
+    assert!(matches!(
+        try_generate_password(&crossed),
+        Err(PassgenError::MinimumExceedsMaximum { .. })
+    ));
+}
+
+#[test]
+fn constrained_entropy_matches_exhaustive_count() {
+    // Two-character passwords over digits and a 2-symbol set with at least one
+    // of each: 2 orderings * 10 * 2 = 40 passwords
+    let opts = PasswordOptions {
+        numbers: true,
+        special: true,
+        symbols: Some("!?".to_string()),
+        ..Default::default()
+    };
+    let entropy = calculate_constrained_entropy(&opts, 2).unwrap();
+    assert!((entropy - 40f64.log2()).abs() < 1e-9);
+
+    // Unconstrained single set reduces to the naive formula
+    let single = PasswordOptions {
+        lowercase: true,
+        ..Default::default()
+    };
+    let entropy = calculate_constrained_entropy(&single, 16).unwrap();
+    assert!((entropy - calculate_entropy(16, 26)).abs() < 1e-9);
+
+    assert_eq!(calculate_constrained_entropy(&opts, 1), None);
+
+    // Two digits and a symbol in any of 3 places: 3 * 10^2 * 2 = 600
+    let two_digits = PasswordOptions {
+        min_numbers: 2,
+        ..opts.clone()
+    };
+    let entropy = calculate_constrained_entropy(&two_digits, 3).unwrap();
+    assert!((entropy - 600f64.log2()).abs() < 1e-9);
+}
+
+#[test]
+fn constrained_entropy_scales_to_long_passwords() {
+    let opts = PasswordOptions {
+        min_numbers: 3,
+        ..all_sets(50_000)
+    };
+    let entropy = calculate_constrained_entropy(&opts, 50_000).unwrap();
+    let naive = calculate_entropy(50_000, get_charset_size(&opts));
+    assert!((naive - entropy).abs() < 1e-6);
+}
+
+fn all_sets(length: usize) -> PasswordOptions {
+    PasswordOptions {
+        length,
+        uppercase: true,
+        lowercase: true,
+        numbers: true,
+        special: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn seeded_generation_is_reproducible() {
+    let opts = all_sets(16);
+    assert_eq!(
+        generate_password_with_rng(&opts, &mut StdRng::seed_from_u64(42)).expose_secret(),
+        "Ls_-oATDMiuk#6N:"
+    );
+    assert_eq!(
+        generate_phonetic_password_with_rng(&opts, &mut StdRng::seed_from_u64(42)).expose_secret(),
+        "heToZ8cowuH%1uXi"
+    );
+    assert_eq!(
+        generate_pattern_password_with_rng("ULLLDDSS", &opts, &mut StdRng::seed_from_u64(42))
+            .expose_secret(),
+        "Dngo64?{"
+    );
+}
+
+#[test]
+fn seeded_batches_match_sequential_generation() {
+    let opts = all_sets(12);
+    let analysis =
+        try_generate_multiple_passwords_with_rng(&opts, 3, &mut StdRng::seed_from_u64(7)).unwrap();
+
+    let mut rng = StdRng::seed_from_u64(7);
+    for pwd_strength in &analysis.passwords {
+        assert_eq!(pwd_strength.password.as_ref().unwrap(), &generate_password_with_rng(&opts, &mut rng));
+    }
+}
 
+#[test]
+fn phonetic_substitutions_never_overwrite_each_other() {
+    let opts = all_sets(16);
+    for seed in 0..200 {
+        let pwd = generate_phonetic_password_with_rng(&opts, &mut StdRng::seed_from_u64(seed));
+        assert_eq!(pwd.chars().count(), 16);
+        assert_eq!(pwd.chars().filter(char::is_ascii_digit).count(), 2, "{}", pwd.expose_secret());
+        assert_eq!(count(&pwd, "!@#$%^&*"), 1, "{}", pwd.expose_secret());
+    }
+}
 
+#[test]
+fn generator_entropy_reflects_each_mode() {
+    let letters_only = PasswordOptions {
+        length: 8,
+        lowercase: true,
+        ..Default::default()
+    };
+    let expected = 4.0 * 21f64.log2() + 4.0 * 5f64.log2();
+    assert!((phonetic_entropy(&letters_only) - expected).abs() < 1e-9);
+
+    // Two digits at distinct positions among 8 replace a quarter of the letters
+    let with_digits = PasswordOptions {
+        numbers: true,
+        ..letters_only.clone()
+    };
+    let expected = expected * 0.75 + 28f64.log2() + 2.0 * 10f64.log2();
+    assert!((phonetic_entropy(&with_digits) - expected).abs() < 1e-9);
+
+    let expected = 2.0 * 26f64.log2() + 10f64.log2() + 26f64.log2();
+    assert!((pattern_entropy("ULDS", &letters_only) - expected).abs() < 1e-9);
+    assert_eq!(pattern_entropy("-x-", &letters_only), 0.0);
+
+    let phonetic = try_generate_smart_password(SmartPasswordMode::Phonetic, &all_sets(16)).unwrap();
+    assert!(phonetic.generator_entropy_bits.unwrap() < phonetic.entropy_bits);
+
+    let pattern =
+        try_generate_smart_password(SmartPasswordMode::Pattern("ULDS".to_string()), &all_sets(4))
+            .unwrap();
+    assert!((pattern.generator_entropy_bits.unwrap() - pattern_entropy("ULDS", &all_sets(4))).abs() < 1e-9);
+
+    let default = try_generate_password_with_strength(&all_sets(16)).unwrap();
+    assert_eq!(default.generator_entropy_bits, Some(default.entropy_bits));
+}
+
+#[test]
+fn phonetic_honors_ambiguous_uppercase_and_symbols() {
+    let opts = PasswordOptions {
+        avoid_ambiguous: true,
+        symbols: Some("~`".to_string()),
+        exclude_chars: "bB".to_string(),
+        ..all_sets(16)
+    };
+    let mut saw_uppercase = false;
+    for _ in 0..100 {
+        let pwd = generate_phonetic_password(&opts);
+        assert_eq!(pwd.chars().count(), 16);
+        assert!(!pwd.chars().any(|c| "IOl01bB".contains(c)), "{}", pwd.expose_secret());
+        assert_eq!(count(&pwd, "~`"), 1, "{}", pwd.expose_secret());
+        assert_eq!(count(&pwd, SPECIAL), 0, "{}", pwd.expose_secret());
+        saw_uppercase |= has_uppercase(&pwd);
+    }
+    assert!(saw_uppercase);
+
+    let lowercase_only = PasswordOptions {
+        length: 12,
+        lowercase: true,
+        ..Default::default()
+    };
+    for _ in 0..50 {
+        let pwd = generate_phonetic_password(&lowercase_only);
+        assert!(pwd.chars().all(|c| c.is_ascii_lowercase()), "{}", pwd.expose_secret());
+    }
+
+    let no_vowels = PasswordOptions {
+        exclude_chars: "aeiou".to_string(),
+        ..lowercase_only
+    };
+    assert_eq!(try_generate_phonetic_password(&no_vowels), Err(PassgenError::NoCharacterSets));
+}
+
+#[test]
+fn pattern_honors_ambiguous_exclusions_and_symbols() {
+    let opts = PasswordOptions {
+        avoid_ambiguous: true,
+        symbols: Some("#".to_string()),
+        exclude_chars: "ABC".to_string(),
+        ..Default::default()
+    };
+    for _ in 0..100 {
+        let pwd = generate_pattern_password("U{4}L{4}D{4}S'-O1'", &opts);
+        let (random, literal) = pwd.split_at(13);
+        assert!(!random.chars().any(|c| "IOl01ABC".contains(c)), "{}", pwd.expose_secret());
+        assert!(random.ends_with('#'), "{}", pwd.expose_secret());
+        // Literals are kept as written
+        assert_eq!(literal, "-O1");
+    }
+
+    let entropy = pattern_entropy("UD", &opts);
+    assert!((entropy - (21f64.log2() + 8f64.log2())).abs() < 1e-9);
+
+    let only_excluded = PasswordOptions {
+        exclude_chars: "0123456789".to_string(),
+        ..Default::default()
+    };
+    assert!(matches!(
+        try_generate_pattern_password("LLD", &only_excluded),
+        Err(PassgenError::InvalidPattern { position: 2, .. })
+    ));
+}
+
+#[test]
+fn user_inputs_score_down_personal_passwords() {
+    let context = AnalysisContext {
+        user_inputs: vec![
+            "Jane Doe".to_string(),
+            "jane.doe@acmecorp.com".to_string(),
+            "AcmeCorp".to_string(),
+        ],
+        ..Default::default()
+    };
+    for password in ["Janedoe2024", "Acmecorp!23"] {
+        let plain = try_analyze_smart(password, &AnalysisContext::default()).unwrap();
+        let personal = try_analyze_smart(password, &context).unwrap();
+        assert!(personal.strength_score < plain.strength_score, "{}", password);
+    }
+
+    let opts = PasswordOptions {
+        user_inputs: context.user_inputs,
+        ..all_sets(11)
+    };
+    let personal = try_analyze_password_strength("Acmecorp!23", &opts).unwrap();
+    assert!(personal.strength_score <= 1);
+}
+
+#[test]
+fn analysis_explains_weak_passwords() {
+    let weak = try_analyze_smart("qwerty1991abcabc", &AnalysisContext::default()).unwrap();
+    let kinds: Vec<MatchKind> = weak.matches.iter().map(|m| m.pattern).collect();
+    assert_eq!(kinds, [MatchKind::Dictionary, MatchKind::Regex, MatchKind::Repeat]);
+    assert_eq!(weak.matches[1].token.expose_secret(), "1991");
+    assert_eq!((weak.matches[1].start, weak.matches[1].end), (6, 9));
+
+    let common = try_analyze_smart("password", &AnalysisContext::default()).unwrap();
+    assert_eq!(common.warning.as_deref(), Some("This is a top-10 common password."));
+    assert!(!common.suggestions.is_empty());
+    assert!(common.guesses_log10 < 1.0);
+
+    let json = serde_json::to_value(&common).unwrap();
+    assert_eq!(json["matches"][0]["pattern"], "dictionary");
+
+    let strong = try_analyze_smart("Tr0ub4dor&3", &AnalysisContext::default()).unwrap();
+    assert!(strong.warning.is_none());
+    assert!(strong.guesses_log10 > 10.0);
+}
+
+#[test]
+fn crack_times_cover_every_attacker_model() {
+    let mut strength = try_analyze_password_strength("abcdefgh", &PasswordOptions {
+        length: 8,
+        lowercase: true,
+        ..Default::default()
+    })
+    .unwrap();
+    let names: Vec<&str> = strength.crack_times.iter().map(|t| t.attacker.as_str()).collect();
+    assert_eq!(names, ["offline_fast_hash", "online_throttled", "online_unthrottled", "offline_slow_hash", "gpu_cluster"]);
+
+    // 26^8 guesses, without truncating the 37.6 bits of entropy to 37
+    let expected = 26f64.powi(8) / 1e10;
+    assert!((strength.crack_time_seconds - expected).abs() / expected < 1e-9);
+
+    let bcrypt: AttackerModel = "bcrypt=2e4".parse().unwrap();
+    strength.apply_attackers(&[bcrypt]);
+    assert_eq!(strength.crack_times.len(), 1);
+    assert!((strength.crack_time_seconds - 26f64.powi(8) / 2e4).abs() < 1e-3);
+
+    assert!("online_throttled".parse::<AttackerModel>().is_ok());
+    assert!("argon2".parse::<AttackerModel>().is_err());
+    assert!("bcrypt=0".parse::<AttackerModel>().is_err());
+}
+
+#[test]
+fn crack_time_uses_zxcvbn_guesses_for_unknown_passwords() {
+    let common = try_analyze_smart("password", &AnalysisContext::default()).unwrap();
+    assert!(common.attack_guesses_log10() < 1.0);
+    assert!(common.crack_time_seconds < 1.0);
+}
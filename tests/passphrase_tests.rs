@@ -0,0 +1,121 @@
+use passgen::{
+    Capitalization, PassphraseOptions, PasswordGenerator, PasswordOptions, SmartPasswordMode,
+    Wordlist, generate_passphrase, generate_passphrase_with_rng, generate_passphrase_with_strength,
+    passphrase_entropy,
+};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+#[test]
+fn bundled_wordlist_has_expected_size() {
+    let wordlist = Wordlist::bip39_english();
+    assert_eq!(wordlist.len(), 2048);
+    assert!((wordlist.entropy_per_word() - 11.0).abs() < 0.001);
+}
+
+#[test]
+fn parses_eff_dice_format_and_plain_lists() {
+    let eff = Wordlist::parse("11111\tabacus\n11112\tabdomen\n11113\tabdominal\n").unwrap();
+    assert_eq!(eff.words(), ["abacus", "abdomen", "abdominal"]);
+
+    let plain = Wordlist::parse("# comment\nalpha\n\nbravo\nalpha\n").unwrap();
+    assert_eq!(plain.words(), ["alpha", "bravo"]);
+
+    assert!(Wordlist::parse("only\n").is_none());
+}
+
+#[test]
+fn passphrase_uses_requested_words_and_separator() {
+    let opts = PassphraseOptions {
+        words: 5,
+        separator: ".".to_string(),
+        ..Default::default()
+    };
+    let phrase = generate_passphrase(&opts);
+    let words: Vec<&str> = phrase.split('.').collect();
+    assert_eq!(words.len(), 5);
+    for word in words {
+        assert!(opts.wordlist.words().iter().any(|w| w == word));
+    }
+}
+
+#[test]
+fn passphrase_capitalization_and_injection() {
+    let opts = PassphraseOptions {
+        words: 4,
+        separator: " ".to_string(),
+        capitalization: Capitalization::Upper,
+        digit: true,
+        symbol: true,
+        ..Default::default()
+    };
+    let phrase = generate_passphrase(&opts);
+    assert!(phrase.chars().any(|c| c.is_ascii_digit()));
+    assert!(phrase.chars().any(|c| "!@#$%^&*".contains(c)));
+    assert!(!phrase.chars().any(|c| c.is_ascii_lowercase()));
+}
+
+#[test]
+fn passphrase_entropy_is_words_times_log2_list_size() {
+    let wordlist = Wordlist::parse("one\ntwo\nthree\nfour\n").unwrap();
+    let opts = PassphraseOptions {
+        words: 6,
+        wordlist,
+        ..Default::default()
+    };
+    assert!((passphrase_entropy(&opts) - 12.0).abs() < 0.001);
+
+    let strength = generate_passphrase_with_strength(&PassphraseOptions::default());
+    assert!((strength.entropy_bits - 66.0).abs() < 0.001);
+}
+
+#[test]
+fn seeded_passphrase_is_reproducible() {
+    let phrase = generate_passphrase_with_rng(
+        &PassphraseOptions::default(),
+        &mut StdRng::seed_from_u64(42),
+    );
+    assert_eq!(
+        phrase.expose_secret(),
+        "captain-man-distance-pepper-gun-angle"
+    );
+}
+
+#[test]
+fn generated_passphrases_are_checked_against_user_inputs() {
+    let phrase_opts = PassphraseOptions {
+        words: 3,
+        ..Default::default()
+    };
+    let analyze = |user_inputs: Vec<String>| {
+        PasswordGenerator::smart_with_rng(
+            SmartPasswordMode::Passphrase(phrase_opts.clone()),
+            PasswordOptions {
+                user_inputs,
+                ..Default::default()
+            },
+            StdRng::seed_from_u64(8),
+        )
+        .unwrap()
+        .analyzed()
+        .next()
+        .unwrap()
+        .unwrap()
+    };
+    let plain = analyze(Vec::new());
+    let words = plain
+        .password
+        .as_ref()
+        .unwrap()
+        .split('-')
+        .map(str::to_string)
+        .collect();
+    let personal = analyze(words);
+    assert!(personal.guesses_log10 < plain.guesses_log10);
+    assert!(personal.matches.iter().any(|m| {
+        m.detail
+            .as_deref()
+            .is_some_and(|detail| detail.starts_with("user inputs"))
+    }));
+    assert_eq!(personal.entropy_bits, passphrase_entropy(&phrase_opts));
+}
@@ -0,0 +1,152 @@
+use passgen::{
+    PassgenError, PasswordGenerator, PasswordOptions, Pattern, SmartPasswordMode, Wordlist,
+};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+fn generate(pattern: &str, seed: u64) -> String {
+    Pattern::parse(pattern)
+        .unwrap()
+        .generate(&mut StdRng::seed_from_u64(seed))
+        .expose_secret()
+        .to_string()
+}
+
+fn error_position(pattern: &str) -> usize {
+    match Pattern::parse(pattern) {
+        Err(PassgenError::InvalidPattern { position, .. }) => position,
+        other => panic!("expected a parse error for {:?}, got {:?}", pattern, other),
+    }
+}
+
+#[test]
+fn license_keys_from_custom_classes_and_groups() {
+    for seed in 0..50 {
+        let key = generate("[A-Z0-9]{4}('-'[A-Z0-9]{4}){2}", seed);
+        let groups: Vec<&str> = key.split('-').collect();
+        assert_eq!(groups.len(), 3, "{}", key);
+        for group in groups {
+            assert_eq!(group.len(), 4);
+            assert!(
+                group
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+            );
+        }
+    }
+}
+
+#[test]
+fn counted_and_ranged_repetition() {
+    for seed in 0..50 {
+        let pwd = generate("U{3}D{4}", seed);
+        assert_eq!(pwd.len(), 7);
+        assert!(pwd[..3].chars().all(|c| c.is_ascii_uppercase()));
+        assert!(pwd[3..].chars().all(|c| c.is_ascii_digit()));
+
+        let ranged = generate("L{2,5}", seed);
+        assert!((2..=5).contains(&ranged.len()), "{}", ranged);
+    }
+}
+
+#[test]
+fn literals_escapes_and_bare_punctuation() {
+    assert_eq!(generate(r#"\U'LDS'"x"-_."#, 1), "ULDSx-_.");
+    assert_eq!(generate(r"[\]]{3}", 1), "]]]");
+    assert_eq!(generate("[-]", 1), "-");
+}
+
+#[test]
+fn optional_groups_are_sometimes_present() {
+    let outputs: Vec<String> = (0..64).map(|seed| generate("D('-'D)?", seed)).collect();
+    assert!(outputs.iter().any(|pwd| pwd.len() == 1));
+    assert!(outputs.iter().any(|pwd| pwd.len() == 3));
+}
+
+#[test]
+fn hex_base32_and_word_classes() {
+    let pwd = generate("h{16}H{16}B{16}", 3);
+    assert!(pwd[..16].chars().all(|c| "0123456789abcdef".contains(c)));
+    assert!(pwd[16..32].chars().all(|c| "0123456789ABCDEF".contains(c)));
+    assert!(
+        pwd[32..]
+            .chars()
+            .all(|c| "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567".contains(c))
+    );
+
+    let phrase = generate("W'.'W", 3);
+    let wordlist = Wordlist::bip39_english();
+    for word in phrase.split('.') {
+        assert!(wordlist.words().iter().any(|w| w == word), "{}", phrase);
+    }
+}
+
+#[test]
+fn invalid_templates_report_positions() {
+    assert_eq!(error_position("UUx"), 2);
+    assert_eq!(error_position("U{3"), 1);
+    assert_eq!(error_position("D{5,2}"), 1);
+    assert_eq!(error_position("LL[a-"), 2);
+    assert_eq!(error_position("[z-a]"), 2);
+    assert_eq!(error_position("(UL"), 0);
+    assert_eq!(error_position("UL)"), 2);
+    assert_eq!(error_position("{2}"), 0);
+    assert_eq!(error_position("'open"), 0);
+    assert_eq!(error_position("D[]"), 1);
+}
+
+#[test]
+fn entropy_counts_slots_and_choices() {
+    let entropy = |pattern: &str| Pattern::parse(pattern).unwrap().entropy();
+    assert!((entropy("[A-F0-9]{8}") - 32.0).abs() < 1e-9);
+    assert!((entropy("h{4}'-'B{2}") - 26.0).abs() < 1e-9);
+    assert!((entropy("W{3}") - 33.0).abs() < 1e-9);
+    // One bit for the count, then 1.5 digits on average
+    assert!((entropy("[0-9]{1,2}") - (1.0 + 1.5 * 10f64.log2())).abs() < 1e-9);
+    // Empty half the time, so not the 11 outputs' log2(11) bits
+    assert!((entropy("D?") - (1.0 + 10f64.log2() / 2.0)).abs() < 1e-9);
+    assert!((entropy("D{0,1}") - (1.0 + 10f64.log2() / 2.0)).abs() < 1e-9);
+    assert!((entropy("('a'D)?") - (1.0 + 10f64.log2() / 2.0)).abs() < 1e-9);
+    assert!((entropy("D{0,8}") - (9f64.log2() + 4.0 * 10f64.log2())).abs() < 1e-9);
+    assert_eq!(entropy("'fixed'"), 0.0);
+}
+
+#[test]
+fn keyspace_counts_every_output() {
+    let keyspace = |pattern: &str| Pattern::parse(pattern).unwrap().keyspace_bits();
+    assert!((keyspace("[A-F0-9]{8}") - 32.0).abs() < 1e-9);
+    // 10 one-digit and 100 two-digit outputs
+    assert!((keyspace("[0-9]{1,2}") - 110f64.log2()).abs() < 1e-9);
+    // The empty output and 10 digits
+    assert!((keyspace("D?") - 11f64.log2()).abs() < 1e-9);
+    assert!((keyspace("D{0,1}") - 11f64.log2()).abs() < 1e-9);
+    assert!((keyspace("('a'D)?") - 11f64.log2()).abs() < 1e-9);
+    assert_eq!(keyspace("'fixed'"), 0.0);
+}
+
+#[test]
+fn nested_repetitions_are_capped_in_total() {
+    assert!(Pattern::parse("U{4096}").is_ok());
+    assert!(Pattern::parse("(U{16}){4096}").is_ok());
+    assert_eq!(error_position("((U{4096}){4096}){4096}"), 1);
+    assert_eq!(error_position("(U{4096}){17}"), 0);
+    assert_eq!(error_position("U{4096}(U{4096}){16}"), 7);
+}
+
+#[test]
+fn empty_output_is_analyzed() {
+    let generator = PasswordGenerator::smart_with_rng(
+        SmartPasswordMode::Pattern("D?".to_string()),
+        PasswordOptions::default(),
+        StdRng::seed_from_u64(1),
+    )
+    .unwrap();
+    let empty = generator
+        .analyzed()
+        .take(100)
+        .map(Result::unwrap)
+        .find(|strength| strength.password.as_deref() == Some(""))
+        .unwrap();
+    assert_eq!(empty.strength_score, 0);
+    assert!((empty.generator_entropy_bits.unwrap() - (1.0 + 10f64.log2() / 2.0)).abs() < 1e-9);
+}
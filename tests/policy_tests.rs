@@ -0,0 +1,110 @@
+use passgen::{
+    PassgenError, PasswordOptions, PasswordPolicy, PolicyViolation, check_policy,
+    try_generate_password,
+};
+
+const LEGACY_POLICY: &str = r#"
+min_length = 12
+max_length = 14
+min_uppercase = 2
+min_numbers = 2
+min_special = 1
+forbidden_chars = "<>|;&"
+max_repeated = 1
+forbidden_substrings = ["acme"]
+required_prefix = "X-"
+"#;
+
+fn default_opts() -> PasswordOptions {
+    PasswordOptions {
+        length: 16,
+        uppercase: true,
+        lowercase: true,
+        numbers: true,
+        special: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn parses_toml_and_json_policies() {
+    let policy = PasswordPolicy::from_toml_str(LEGACY_POLICY).unwrap();
+    assert_eq!(policy.min_length, 12);
+    assert_eq!(policy.max_length, Some(14));
+    assert_eq!(policy.required_prefix.as_deref(), Some("X-"));
+
+    let json = PasswordPolicy::from_json_str(r#"{"min_length": 8, "min_numbers": 3}"#).unwrap();
+    assert_eq!(json.min_length, 8);
+    assert_eq!(json.min_numbers, 3);
+    assert_eq!(json.max_length, None);
+}
+
+#[test]
+fn unknown_policy_fields_are_rejected() {
+    let err = PasswordPolicy::from_toml_str("min_lenght = 8").unwrap_err();
+    assert!(matches!(err, PassgenError::Policy(_)));
+}
+
+#[test]
+fn check_policy_reports_each_violation() {
+    let policy = PasswordPolicy::from_toml_str(LEGACY_POLICY).unwrap();
+    let violations = check_policy("aaACME<", &policy);
+
+    assert!(violations.contains(&PolicyViolation::TooShort {
+        length: 7,
+        min_length: 12
+    }));
+    assert!(violations.contains(&PolicyViolation::ForbiddenCharacter { character: '<' }));
+    assert!(violations.contains(&PolicyViolation::RepeatedRun {
+        run: "aa".to_string(),
+        max_repeated: 1
+    }));
+    assert!(violations.contains(&PolicyViolation::ForbiddenSubstring {
+        substring: "acme".to_string()
+    }));
+    assert!(violations.contains(&PolicyViolation::MissingPrefix {
+        prefix: "X-".to_string()
+    }));
+    assert!(violations.iter().any(|v| matches!(
+        v,
+        PolicyViolation::TooFew { class, count: 0, min: 2 } if class == "numbers"
+    )));
+
+    assert!(check_policy("X-Ab3$cD4e", &PasswordPolicy::default()).is_empty());
+}
+
+#[test]
+fn generated_passwords_satisfy_policy() {
+    let policy = PasswordPolicy::from_toml_str(LEGACY_POLICY).unwrap();
+    let opts = PasswordOptions {
+        policy: Some(policy.clone()),
+        ..default_opts()
+    };
+    for _ in 0..50 {
+        let pwd = try_generate_password(&opts).unwrap();
+        assert_eq!(
+            check_policy(&pwd, &policy),
+            Vec::new(),
+            "{}",
+            pwd.expose_secret()
+        );
+        assert_eq!(pwd.chars().count(), 14);
+    }
+}
+
+#[test]
+fn unsatisfiable_policy_is_an_error() {
+    let policy = PasswordPolicy {
+        max_length: Some(4),
+        required_prefix: Some("LONGPREFIX".to_string()),
+        ..Default::default()
+    };
+    let opts = PasswordOptions {
+        policy: Some(policy),
+        ..default_opts()
+    };
+    assert!(matches!(
+        try_generate_password(&opts),
+        Err(PassgenError::PolicyUnsatisfiable(_))
+    ));
+}
@@ -0,0 +1,72 @@
+use passgen::{PasswordOptions, SecretString, analyze_password_strength};
+
+#[test]
+fn debug_output_is_redacted() {
+    let secret = SecretString::from("hunter2-correct-horse");
+    assert_eq!(format!("{:?}", secret), "[REDACTED]");
+
+    let strength = analyze_password_strength("hunter2-correct-horse", &PasswordOptions::default());
+    let debug = format!("{:?}", strength);
+    assert!(!debug.contains("hunter2"), "{}", debug);
+}
+
+#[test]
+fn growing_keeps_the_contents() {
+    let mut secret = SecretString::new();
+    for word in ["correct", "horse", "battery", "staple"] {
+        secret.push_str(word);
+        secret.push('-');
+    }
+    assert_eq!(secret.expose_secret(), "correct-horse-battery-staple-");
+
+    let collected: SecretString = "ünïcödé".chars().collect();
+    assert_eq!(collected.expose_secret(), "ünïcödé");
+}
+
+#[test]
+fn serializes_as_a_plain_string() {
+    let secret = SecretString::from("s3cret!");
+    let json = serde_json::to_string(&secret).unwrap();
+    assert_eq!(json, "\"s3cret!\"");
+    let back: SecretString = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, secret);
+}
+
+#[test]
+fn omitted_passwords_are_left_out_of_serialized_analysis() {
+    let mut strength = analyze_password_strength("s3cret!", &PasswordOptions::default());
+    assert!(serde_json::to_value(&strength).unwrap()["password"] == "s3cret!");
+
+    strength.omit_password();
+    assert!(strength.password.is_none());
+    let value = serde_json::to_value(&strength).unwrap();
+    assert!(value.get("password").is_none());
+    assert!(value.get("entropy_bits").is_some());
+}
+
+#[test]
+fn match_tokens_are_redacted_and_omitted() {
+    let mut strength = analyze_password_strength("Tr0ub4dor&3horse", &PasswordOptions::default());
+    assert!(strength.matches.len() > 1);
+    assert!(
+        strength
+            .matches
+            .iter()
+            .any(|m| m.detail.as_deref().is_some_and(|d| d.contains("horse")))
+    );
+
+    let debug = format!("{:?}", strength);
+    for part in ["Tr0ub4dor", "horse"] {
+        assert!(!debug.contains(part), "{}", debug);
+    }
+
+    strength.omit_password();
+    assert!(strength.matches.iter().all(|m| m.token.is_empty()));
+    let json = serde_json::to_string(&strength).unwrap();
+    for part in ["Tr0ub4dor", "horse", "&3"] {
+        assert!(!json.contains(part), "{}", json);
+    }
+    let value = serde_json::to_value(&strength).unwrap();
+    assert!(value["matches"][0].get("token").is_none());
+    assert!(value["matches"][0].get("guesses_log10").is_some());
+}
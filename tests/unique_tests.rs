@@ -0,0 +1,98 @@
+use passgen::{
+    BannedWords, PassgenError, PasswordAnalysis, PasswordGenerator, PasswordOptions,
+    SmartPasswordMode, collision_probability, generate_multiple_passwords, keyspace_size,
+    password_entropy, try_generate_unique_passwords_with_rng,
+};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::collections::HashSet;
+
+fn digits(length: usize) -> PasswordOptions {
+    PasswordOptions {
+        length,
+        numbers: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn unique_batches_can_use_the_whole_space() {
+    assert_eq!(keyspace_size(password_entropy(&digits(3))), 1000.0);
+
+    let analysis =
+        try_generate_unique_passwords_with_rng(&digits(3), 1000, &mut StdRng::seed_from_u64(2))
+            .unwrap();
+    let distinct: HashSet<_> = analysis.passwords.iter().map(|p| &p.password).collect();
+    assert_eq!(distinct.len(), 1000);
+
+    assert!(matches!(
+        try_generate_unique_passwords_with_rng(&digits(3), 1001, &mut StdRng::seed_from_u64(2)),
+        Err(PassgenError::Unique(_))
+    ));
+}
+
+#[test]
+fn smart_generators_can_be_unique() {
+    let generator = PasswordGenerator::smart_with_rng(
+        SmartPasswordMode::Pattern("D{2}".to_string()),
+        digits(2),
+        StdRng::seed_from_u64(3),
+    )
+    .unwrap();
+    let distinct: HashSet<_> = generator.unique(100).unwrap().take(100).collect();
+    assert_eq!(distinct.len(), 100);
+
+    // The empty password and ten digits
+    let optional = |count| {
+        PasswordGenerator::smart_with_rng(
+            SmartPasswordMode::Pattern("D?".to_string()),
+            digits(1),
+            StdRng::seed_from_u64(3),
+        )
+        .unwrap()
+        .unique(count)
+    };
+    let distinct: HashSet<_> = optional(11).unwrap().take(11).collect();
+    assert_eq!(distinct.len(), 11);
+    assert!(matches!(optional(12), Err(PassgenError::Unique(_))));
+}
+
+#[test]
+fn running_out_of_unbanned_passwords_is_an_error() {
+    // 100 two-digit passwords are estimated, but only 81 avoid the digit 1
+    let opts = PasswordOptions {
+        banned_words: Some(BannedWords::new(["1"])),
+        ..digits(2)
+    };
+    let mut generator = PasswordGenerator::with_rng(opts, StdRng::seed_from_u64(4))
+        .unwrap()
+        .unique(100)
+        .unwrap();
+    for _ in 0..81 {
+        generator.try_next().unwrap();
+    }
+    assert!(matches!(generator.try_next(), Err(PassgenError::Unique(_))));
+}
+
+#[test]
+fn collision_probability_follows_the_birthday_problem() {
+    let days = 365f64.log2();
+    assert!((collision_probability(23, days) - 0.5073).abs() < 1e-4);
+    assert_eq!(collision_probability(1, days), 0.0);
+    assert_eq!(collision_probability(366, days), 1.0);
+
+    // 10^6 draws from 2^128: n^2 / 2N
+    let p = collision_probability(1_000_000, 128.0);
+    assert!((p / 1.4693679e-27 - 1.0).abs() < 1e-6);
+
+    assert!(
+        generate_multiple_passwords(&digits(4), 100)
+            .collision_probability
+            .unwrap()
+            > 0.39
+    );
+    assert_eq!(
+        PasswordAnalysis::from_passwords(Vec::new()).collision_probability,
+        None
+    );
+}